@@ -0,0 +1,124 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_newton::*;
+
+const RADIUS: f32 = 0.5;
+
+// Where a particle rests when nobody is dragging it
+#[derive(Component)]
+struct Home(Vec2);
+
+#[derive(Component)]
+struct Dragged;
+
+fn resting_spring(home: Vec2) -> SpringConstraint {
+    SpringConstraint {
+        stiffness: 20.,
+        damping: 2.,
+        ..SpringConstraint::new(home)
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut sphere_mesh = Sphere::new(RADIUS).mesh().build();
+    sphere_mesh
+        .generate_tangents()
+        .expect("Failed to generate tangents");
+    let sphere = meshes.add(sphere_mesh);
+
+    let white = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        ..default()
+    });
+
+    for x in -3..=3 {
+        for y in -2..=2 {
+            let home = Vec2::new(x as f32, y as f32) * 1.5;
+            commands.spawn((
+                Mesh3d(sphere.clone()),
+                MeshMaterial3d(white.clone()),
+                Particle::new_with_pos_and_vel(home, Vec2::ZERO),
+                CircleCollider { radius: RADIUS },
+                resting_spring(home),
+                Home(home),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(Vec3::new(0., 0., 100.)),
+        Projection::Orthographic(OrthographicProjection {
+            scale: 0.01,
+            ..OrthographicProjection::default_3d()
+        }),
+    ));
+}
+
+fn cursor_world_pos(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let (camera, camera_transform) = *camera;
+    let ray = camera
+        .viewport_to_world(camera_transform, window.cursor_position()?)
+        .ok()?;
+    let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z))?;
+    Some(ray.get_point(distance).truncate())
+}
+
+fn drag(
+    In(cursor): In<Option<Vec2>>,
+    mut commands: Commands,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut particles: Query<(Entity, &Pos, &Home, &mut SpringConstraint, Has<Dragged>)>,
+) {
+    if buttons.just_released(MouseButton::Left) {
+        for (entity, _, home, mut spring, dragged) in &mut particles {
+            if dragged {
+                *spring = resting_spring(home.0);
+                commands.entity(entity).remove::<Dragged>();
+            }
+        }
+    }
+
+    let Some(cursor) = cursor else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        let picked = particles
+            .iter()
+            .filter(|(_, pos, ..)| pos.0.distance(cursor) < RADIUS)
+            .min_by(|(_, a, ..), (_, b, ..)| a.0.distance(cursor).total_cmp(&b.0.distance(cursor)));
+        if let Some((entity, ..)) = picked {
+            commands.entity(entity).insert(Dragged);
+        }
+    }
+
+    // A stiff spring towards the cursor acts as the mouse joint
+    for (_, _, _, mut spring, dragged) in &mut particles {
+        if dragged {
+            *spring = SpringConstraint {
+                stiffness: 500.,
+                damping: 40.,
+                ..SpringConstraint::new(cursor)
+            };
+        }
+    }
+}
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, startup)
+        .add_systems(Update, cursor_world_pos.pipe(drag))
+        .add_plugins(NewtonPlugin)
+        .insert_resource(Gravity(Vec2::ZERO))
+        .run();
+}
@@ -1,5 +1,3 @@
-use bevy::prelude;
-
 use crate::*;
 
 #[derive(Component, Debug, Default)]
@@ -17,11 +15,80 @@ impl Default for Mass {
     }
 }
 
+/// Force applied during the next step, cleared once it has been integrated
+#[derive(Component, Debug, Default)]
+pub struct Force(pub Vec2);
+
+/// Offset of the center of mass from the body's `Pos`
+#[derive(Component, Debug, Default)]
+pub struct CenterOfMass(pub Vec2);
+
+/// Moment of inertia around the center of mass
+#[derive(Component, Debug)]
+pub struct Inertia(pub f32);
+
+impl Default for Inertia {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Mass per unit area. Bodies with a density get their mass properties computed from their colliders
+#[derive(Component, Debug)]
+pub struct Density(pub f32);
+
+impl Default for Density {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct CircleCollider {
+    pub radius: f32,
+}
+
+impl Default for CircleCollider {
+    fn default() -> Self {
+        Self { radius: 0.5 }
+    }
+}
+
+/// Pulls a body towards `target` like a damped spring, e.g. to drag it around with the mouse
+#[derive(Component, Debug)]
+pub struct SpringConstraint {
+    pub target: Vec2,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl SpringConstraint {
+    pub fn new(target: Vec2) -> Self {
+        Self {
+            target,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for SpringConstraint {
+    fn default() -> Self {
+        Self {
+            target: Vec2::ZERO,
+            stiffness: 100.,
+            damping: 10.,
+        }
+    }
+}
+
 #[derive(Bundle, Default)]
 pub struct Particle {
     pub pos: Pos,
     pub prev_pos: PrevPos,
     pub mass: Mass,
+    pub force: Force,
+    pub center_of_mass: CenterOfMass,
+    pub inertia: Inertia,
 }
 
 impl Particle {
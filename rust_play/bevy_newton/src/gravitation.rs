@@ -0,0 +1,168 @@
+use crate::*;
+
+// Deep enough for any sane scene, stops bodies at the same position from splitting forever
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug)]
+struct Node {
+    center: Vec2,
+    half_size: f32,
+    mass: f32,
+    center_of_mass: Vec2,
+    body: Option<(Vec2, f32)>,
+    /// Index of the first of four consecutive child nodes
+    children: Option<usize>,
+}
+
+impl Node {
+    fn new(center: Vec2, half_size: f32) -> Self {
+        Self {
+            center,
+            half_size,
+            mass: 0.,
+            center_of_mass: Vec2::ZERO,
+            body: None,
+            children: None,
+        }
+    }
+
+    fn quadrant(&self, pos: Vec2) -> usize {
+        (pos.x >= self.center.x) as usize + 2 * (pos.y >= self.center.y) as usize
+    }
+}
+
+/// Barnes–Hut quadtree storing the total mass and center of mass of every cell
+#[derive(Debug)]
+pub(crate) struct QuadTree {
+    nodes: Vec<Node>,
+}
+
+impl QuadTree {
+    pub(crate) fn new(bodies: &[(Vec2, f32)]) -> Self {
+        let (min, max) = bodies.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &(pos, _)| (min.min(pos), max.max(pos)),
+        );
+        let half_size = ((max - min).max_element() / 2.).max(f32::EPSILON);
+        let mut tree = Self {
+            nodes: vec![Node::new((min + max) / 2., half_size)],
+        };
+        for &(pos, mass) in bodies {
+            tree.insert(0, pos, mass, 0);
+        }
+        tree
+    }
+
+    fn insert(&mut self, index: usize, pos: Vec2, mass: f32, depth: usize) {
+        let node = &mut self.nodes[index];
+        let was_empty = node.body.is_none() && node.children.is_none();
+
+        let total_mass = node.mass + mass;
+        if total_mass > 0. {
+            node.center_of_mass = (node.center_of_mass * node.mass + pos * mass) / total_mass;
+        }
+        node.mass = total_mass;
+
+        if let Some(first) = node.children {
+            let quadrant = node.quadrant(pos);
+            self.insert(first + quadrant, pos, mass, depth + 1);
+            return;
+        }
+        if was_empty {
+            node.body = Some((pos, mass));
+            return;
+        }
+        // Too deep to split any further, the leaf keeps the combined mass
+        if depth >= MAX_DEPTH {
+            return;
+        }
+
+        let (center, half_size) = (node.center, node.half_size / 2.);
+        let existing = node.body.take();
+        let first = self.nodes.len();
+        self.nodes[index].children = Some(first);
+        for offset in [
+            Vec2::new(-1., -1.),
+            Vec2::new(1., -1.),
+            Vec2::new(-1., 1.),
+            Vec2::new(1., 1.),
+        ] {
+            self.nodes
+                .push(Node::new(center + offset * half_size, half_size));
+        }
+        for (pos, mass) in existing.into_iter().chain([(pos, mass)]) {
+            let quadrant = self.nodes[index].quadrant(pos);
+            self.insert(first + quadrant, pos, mass, depth + 1);
+        }
+    }
+
+    /// Gravitational acceleration at `pos`, treating cells that look smaller than `theta`
+    /// from there as a single mass
+    pub(crate) fn acceleration(
+        &self,
+        pos: Vec2,
+        gravitation: &MutualGravitation,
+        theta: f32,
+    ) -> Vec2 {
+        let mut acceleration = Vec2::ZERO;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.mass <= 0. {
+                continue;
+            }
+            let r = node.center_of_mass - pos;
+            let size = 2. * node.half_size;
+            match node.children {
+                Some(first) if size * size >= theta * theta * r.length_squared() => {
+                    stack.extend(first..first + 4);
+                }
+                _ => acceleration += gravitation.acceleration(r, node.mass),
+            }
+        }
+        acceleration
+    }
+}
+
+impl MutualGravitation {
+    /// Softened acceleration towards a mass at offset `r`. Without softening a mass right on
+    /// top, like a body's own leaf in the Barnes–Hut tree, doesn't pull at all.
+    pub fn acceleration(&self, r: Vec2, mass: f32) -> Vec2 {
+        let distance_sqr = r.length_squared() + self.softening * self.softening;
+        if distance_sqr == 0. {
+            return Vec2::ZERO;
+        }
+        self.constant * mass * r / (distance_sqr * distance_sqr.sqrt())
+    }
+}
+
+// Accumulate the pull of every body on every other body
+pub(crate) fn apply_mutual_gravitation(
+    mut query: Query<(&Pos, &Mass, &mut Force)>,
+    gravitation: Res<MutualGravitation>,
+) {
+    match gravitation.barnes_hut_theta {
+        Some(theta) => {
+            let bodies: Vec<(Vec2, f32)> =
+                query.iter().map(|(pos, mass, _)| (pos.0, mass.0)).collect();
+            if bodies.is_empty() {
+                return;
+            }
+            let tree = QuadTree::new(&bodies);
+            for (pos, mass, mut force) in query.iter_mut() {
+                force.0 += mass.0 * tree.acceleration(pos.0, &gravitation, theta);
+            }
+        }
+        None => {
+            // Equal and opposite forces, so momentum is conserved exactly
+            let mut iter = query.iter_combinations_mut();
+            while let Some([(pos_a, mass_a, mut force_a), (pos_b, mass_b, mut force_b)]) =
+                iter.fetch_next()
+            {
+                let pull = mass_a.0 * gravitation.acceleration(pos_b.0 - pos_a.0, mass_b.0);
+                force_a.0 += pull;
+                force_b.0 -= pull;
+            }
+        }
+    }
+}
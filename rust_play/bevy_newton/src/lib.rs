@@ -1,37 +1,147 @@
 use bevy::prelude::*;
 
 mod components;
+mod gravitation;
+mod mass;
+mod resources;
 
 pub use components::*;
+use gravitation::apply_mutual_gravitation;
+pub use mass::*;
+pub use resources::*;
 
 pub const DELTA_TIME: f32 = 1. / 60.;
 
+// Accumulate the pull of spring constraints towards their targets
+fn apply_springs(mut query: Query<(&mut Force, &Pos, &PrevPos, &SpringConstraint)>) {
+    for (mut force, pos, prev_pos, spring) in query.iter_mut() {
+        let velocity = (pos.0 - prev_pos.0) / DELTA_TIME;
+        force.0 += spring.stiffness * (spring.target - pos.0) - spring.damping * velocity;
+    }
+}
+
 // Move objects in the physics world
-fn simulate(mut query: Query<(&mut Pos, &mut PrevPos, &Mass)>) {
-    for (mut pos, mut prev_pos, mass) in query.iter_mut() {
-        let gravity = Vec2::new(0., -9.81);
-        let gravitation_force = mass.0 * gravity;
-        let external_forces = gravitation_force;
+fn simulate(
+    mut query: Query<(&mut Pos, &mut PrevPos, &Mass, Option<&mut Force>)>,
+    gravity: Res<Gravity>,
+) {
+    for (mut pos, mut prev_pos, mass, force) in query.iter_mut() {
+        let gravitation_force = mass.0 * gravity.0;
+        let mut external_forces = gravitation_force;
+        if let Some(mut force) = force {
+            external_forces += std::mem::take(&mut force.0);
+        }
         let velocity = (pos.0 - prev_pos.0) / DELTA_TIME + DELTA_TIME * external_forces / mass.0;
         prev_pos.0 = pos.0;
         pos.0 = prev_pos.0 + velocity * DELTA_TIME;
     }
 }
 
-// Copy positions from the physics world to render world
-fn sync_transform(mut query: Query<(&mut Transform, &Pos)>) {
-    for (mut transform, pos) in query.iter_mut() {
-        transform.translation = pos.0.extend(0.);
+// Push overlapping circles apart, weighted by inverse mass so momentum is conserved.
+// Colliders on child entities move with the body they are attached to.
+fn solve_collisions(
+    mut bodies: Query<(&mut Pos, &Mass)>,
+    colliders: Query<(
+        Entity,
+        &CircleCollider,
+        Option<&ChildOf>,
+        Option<&Transform>,
+    )>,
+    globals: Query<&GlobalTransform>,
+) {
+    // (body, offset from the body's `Pos`, radius)
+    let circles: Vec<(Entity, Vec2, f32)> = colliders
+        .iter()
+        .filter_map(|(entity, circle, child_of, transform)| {
+            if bodies.contains(entity) {
+                return Some((entity, Vec2::ZERO, circle.radius));
+            }
+            let body = child_of?.parent();
+            let local = transform.map_or(Vec3::ZERO, |t| t.translation);
+            // The offset is in the body's space, so it turns with the body. Scale is ignored, like
+            // it is for the radius and the mass properties.
+            let offset = globals
+                .get(body)
+                .map_or(local, |global| global.rotation() * local)
+                .truncate();
+            bodies
+                .contains(body)
+                .then_some((body, offset, circle.radius))
+        })
+        .collect();
+
+    for (i, &(body_a, offset_a, radius_a)) in circles.iter().enumerate() {
+        for &(body_b, offset_b, radius_b) in &circles[i + 1..] {
+            if body_a == body_b {
+                continue;
+            }
+            let Ok([(mut pos_a, mass_a), (mut pos_b, mass_b)]) =
+                bodies.get_many_mut([body_a, body_b])
+            else {
+                continue;
+            };
+
+            let ab = (pos_b.0 + offset_b) - (pos_a.0 + offset_a);
+            let combined_radius = radius_a + radius_b;
+            let ab_sqr_len = ab.length_squared();
+            if ab_sqr_len < combined_radius * combined_radius {
+                let ab_length = ab_sqr_len.sqrt();
+                let penetration_depth = combined_radius - ab_length;
+                let n = ab.normalize_or_zero();
+
+                let w_a = 1. / mass_a.0;
+                let w_b = 1. / mass_b.0;
+                let w_sum = w_a + w_b;
+
+                pos_a.0 -= n * penetration_depth * w_a / w_sum;
+                pos_b.0 += n * penetration_depth * w_b / w_sum;
+            }
+        }
+    }
+}
+
+// Copy positions from the physics world to render world. `Pos` is in world space, so bodies
+// with a parent are moved into the parent's local space.
+fn sync_transform(
+    mut query: Query<(&mut Transform, &Pos, Option<&ChildOf>)>,
+    parents: Query<&GlobalTransform>,
+) {
+    for (mut transform, pos, child_of) in query.iter_mut() {
+        let parent = child_of.and_then(|child_of| parents.get(child_of.parent()).ok());
+        match parent {
+            Some(parent) => {
+                let local = parent.affine().inverse().transform_point3(pos.0.extend(0.));
+                transform.translation = local.truncate().extend(transform.translation.z);
+            }
+            None => transform.translation = pos.0.extend(0.),
+        }
     }
     // println!("debug!!!!!!!!!!!!!!!");
 }
 
+/// The physics step, for ordering systems before or after it in `FixedUpdate`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NewtonSet;
+
 #[derive(Debug, Default)]
 pub struct NewtonPlugin;
 
 impl Plugin for NewtonPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, (simulate, sync_transform))
+        app.init_resource::<Gravity>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    update_mass_properties,
+                    apply_springs,
+                    apply_mutual_gravitation.run_if(resource_exists::<MutualGravitation>),
+                    simulate,
+                    solve_collisions,
+                    sync_transform,
+                )
+                    .chain()
+                    .in_set(NewtonSet),
+            )
             // configure our fixed timestep schedule to run every DELTA_TIME
             .insert_resource(Time::<Fixed>::from_seconds(DELTA_TIME as f64));
     }
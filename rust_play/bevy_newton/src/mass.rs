@@ -0,0 +1,130 @@
+use bevy::platform::collections::HashSet;
+
+use crate::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    /// Relative to the origin of the shape's owner
+    pub center_of_mass: Vec2,
+    /// Around `center_of_mass`
+    pub inertia: f32,
+}
+
+impl MassProperties {
+    /// Moves the properties by `offset`, keeping the inertia around the new center of mass
+    pub fn translated(self, offset: Vec2) -> Self {
+        Self {
+            center_of_mass: self.center_of_mass + offset,
+            ..self
+        }
+    }
+
+    /// Combines two sets of properties using the parallel axis theorem
+    pub fn combine(self, other: Self) -> Self {
+        let mass = self.mass + other.mass;
+        if mass <= 0. {
+            return Self::default();
+        }
+        let center_of_mass =
+            (self.center_of_mass * self.mass + other.center_of_mass * other.mass) / mass;
+        let inertia = self.inertia
+            + self.mass * self.center_of_mass.distance_squared(center_of_mass)
+            + other.inertia
+            + other.mass * other.center_of_mass.distance_squared(center_of_mass);
+        Self {
+            mass,
+            center_of_mass,
+            inertia,
+        }
+    }
+}
+
+impl CircleCollider {
+    pub fn mass_properties(&self, density: f32) -> MassProperties {
+        let mass = density * std::f32::consts::PI * self.radius * self.radius;
+        MassProperties {
+            mass,
+            center_of_mass: Vec2::ZERO,
+            inertia: 0.5 * mass * self.radius * self.radius,
+        }
+    }
+}
+
+// Recompute mass, center of mass and inertia of bodies with a `Density` whenever their own
+// collider or the colliders of their children change
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_mass_properties(
+    mut bodies: Query<
+        (
+            Entity,
+            Ref<Density>,
+            Option<Ref<CircleCollider>>,
+            Option<Ref<Children>>,
+            &mut Mass,
+            &mut CenterOfMass,
+            &mut Inertia,
+        ),
+        With<Pos>,
+    >,
+    child_colliders: Query<(&CircleCollider, &Transform, Option<&Density>)>,
+    changed_child_colliders: Query<
+        &ChildOf,
+        Or<(
+            Changed<CircleCollider>,
+            Changed<Transform>,
+            Changed<Density>,
+        )>,
+    >,
+    mut removed_colliders: RemovedComponents<CircleCollider>,
+    parents: Query<&ChildOf>,
+) {
+    let mut dirty: HashSet<Entity> = changed_child_colliders
+        .iter()
+        .map(ChildOf::parent)
+        .collect();
+    for entity in removed_colliders.read() {
+        dirty.insert(entity);
+        if let Ok(child_of) = parents.get(entity) {
+            dirty.insert(child_of.parent());
+        }
+    }
+
+    for (entity, density, collider, children, mut mass, mut center_of_mass, mut inertia) in
+        &mut bodies
+    {
+        let changed = density.is_changed()
+            || collider
+                .as_ref()
+                .is_some_and(|collider| collider.is_changed())
+            || children
+                .as_ref()
+                .is_some_and(|children| children.is_changed())
+            || dirty.contains(&entity);
+        if !changed {
+            continue;
+        }
+
+        let mut properties = collider
+            .map(|collider| collider.mass_properties(density.0))
+            .unwrap_or_default();
+        for &child in children.as_deref().into_iter().flatten() {
+            let Ok((collider, transform, child_density)) = child_colliders.get(child) else {
+                continue;
+            };
+            let child_density = child_density.map_or(density.0, |density| density.0);
+            properties = properties.combine(
+                collider
+                    .mass_properties(child_density)
+                    .translated(transform.translation.truncate()),
+            );
+        }
+
+        // A body without any colliders keeps whatever mass it was given
+        if properties.mass > 0. {
+            mass.0 = properties.mass;
+            center_of_mass.0 = properties.center_of_mass;
+            inertia.0 = properties.inertia;
+        }
+    }
+}
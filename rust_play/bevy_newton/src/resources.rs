@@ -0,0 +1,30 @@
+use crate::*;
+
+#[derive(Resource, Debug)]
+pub struct Gravity(pub Vec2);
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self(Vec2::new(0., -9.81))
+    }
+}
+
+/// Makes every body attract every other body, usually combined with a zero [`Gravity`]
+#[derive(Resource, Debug)]
+pub struct MutualGravitation {
+    pub constant: f32,
+    /// Plummer softening length, keeps close encounters from blowing up
+    pub softening: f32,
+    /// Opening angle of the Barnes–Hut approximation. `None` sums over all pairs exactly.
+    pub barnes_hut_theta: Option<f32>,
+}
+
+impl Default for MutualGravitation {
+    fn default() -> Self {
+        Self {
+            constant: 6.674_30e-11,
+            softening: 0.01,
+            barnes_hut_theta: None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+fn spawn_ball(test_app: &mut TestApp, pos: Vec2, vel: Vec2, mass: f32) -> Entity {
+    test_app.spawn((
+        Particle {
+            mass: Mass(mass),
+            ..Particle::new_with_pos_and_vel(pos, vel)
+        },
+        CircleCollider { radius: 0.5 },
+    ))
+}
+
+#[test]
+fn head_on_collision_conserves_momentum() {
+    let mut test_app = TestApp::without_gravity();
+    let a = spawn_ball(&mut test_app, Vec2::new(-2., 0.), Vec2::new(3., 0.), 1.);
+    let b = spawn_ball(&mut test_app, Vec2::new(2., 0.), Vec2::new(-1., 0.), 3.);
+    let bodies = [a, b];
+
+    let initial = test_app.momentum(&bodies);
+    for _ in 0..120 {
+        test_app.step(1);
+        let momentum = test_app.momentum(&bodies);
+        assert!(momentum.distance(initial) < 1e-3, "{momentum} vs {initial}");
+    }
+    // The balls did actually meet and separate the overlap
+    assert!(test_app.pos(a).distance(test_app.pos(b)) >= 1. - 1e-4);
+}
+
+#[test]
+fn oblique_collisions_conserve_momentum() {
+    let mut test_app = TestApp::without_gravity();
+    let bodies = [
+        spawn_ball(&mut test_app, Vec2::new(-3., 0.2), Vec2::new(4., 0.), 2.),
+        spawn_ball(&mut test_app, Vec2::new(0., 0.), Vec2::ZERO, 1.),
+        spawn_ball(&mut test_app, Vec2::new(2., -1.5), Vec2::new(-1., 2.), 0.5),
+    ];
+
+    let initial = test_app.momentum(&bodies);
+    test_app.step(240);
+    let momentum = test_app.momentum(&bodies);
+    assert!(momentum.distance(initial) < 1e-3, "{momentum} vs {initial}");
+}
+
+#[test]
+fn collisions_never_add_energy() {
+    let mut test_app = TestApp::without_gravity();
+    let a = spawn_ball(&mut test_app, Vec2::new(-2., 0.1), Vec2::new(2., 0.), 1.);
+    let b = spawn_ball(&mut test_app, Vec2::new(2., -0.1), Vec2::new(-2., 0.), 1.);
+    let bodies = [a, b];
+
+    let initial = test_app.kinetic_energy(&bodies);
+    for _ in 0..120 {
+        test_app.step(1);
+        assert!(test_app.kinetic_energy(&bodies) <= initial + 1e-3);
+    }
+}
+
+#[test]
+fn separated_balls_do_not_interact() {
+    let mut test_app = TestApp::without_gravity();
+    let a = spawn_ball(&mut test_app, Vec2::new(-2., 0.), Vec2::ZERO, 1.);
+    let b = spawn_ball(&mut test_app, Vec2::new(2., 0.), Vec2::ZERO, 1.);
+    test_app.step(60);
+    assert_eq!(test_app.pos(a), Vec2::new(-2., 0.));
+    assert_eq!(test_app.pos(b), Vec2::new(2., 0.));
+}
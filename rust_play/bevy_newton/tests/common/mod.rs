@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_newton::*;
+
+/// A headless app running [`NewtonPlugin`] and transform propagation where every `step`
+/// advances exactly one fixed physics tick, independent of wall-clock time.
+pub struct TestApp {
+    pub app: App,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, NewtonPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                DELTA_TIME as f64,
+            )));
+        // The very first update only initializes the clocks and never advances time
+        app.update();
+        Self { app }
+    }
+
+    pub fn without_gravity() -> Self {
+        let mut test_app = Self::new();
+        test_app.app.insert_resource(Gravity(Vec2::ZERO));
+        test_app
+    }
+
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        self.app.world_mut().spawn(bundle).id()
+    }
+
+    pub fn step(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    pub fn pos(&self, entity: Entity) -> Vec2 {
+        self.app.world().get::<Pos>(entity).unwrap().0
+    }
+
+    /// Velocity implied by the last step, as used by the integrator
+    pub fn vel(&self, entity: Entity) -> Vec2 {
+        let prev_pos = self.app.world().get::<PrevPos>(entity).unwrap().0;
+        (self.pos(entity) - prev_pos) / DELTA_TIME
+    }
+
+    pub fn mass(&self, entity: Entity) -> f32 {
+        self.app.world().get::<Mass>(entity).unwrap().0
+    }
+
+    pub fn momentum(&self, entities: &[Entity]) -> Vec2 {
+        entities.iter().map(|&e| self.mass(e) * self.vel(e)).sum()
+    }
+
+    pub fn kinetic_energy(&self, entities: &[Entity]) -> f32 {
+        entities
+            .iter()
+            .map(|&e| 0.5 * self.mass(e) * self.vel(e).length_squared())
+            .sum()
+    }
+
+    pub fn elapsed_ticks(&self) -> u32 {
+        let elapsed = self
+            .app
+            .world()
+            .resource::<Time<Fixed>>()
+            .elapsed_secs_f64();
+        (elapsed / DELTA_TIME as f64).round() as u32
+    }
+}
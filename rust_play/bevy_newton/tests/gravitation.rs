@@ -0,0 +1,137 @@
+mod common;
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+fn gravitation_app(gravitation: MutualGravitation) -> TestApp {
+    let mut test_app = TestApp::without_gravity();
+    test_app.app.insert_resource(gravitation);
+    test_app
+}
+
+fn spawn_body(test_app: &mut TestApp, pos: Vec2, vel: Vec2, mass: f32) -> Entity {
+    test_app.spawn(Particle {
+        mass: Mass(mass),
+        ..Particle::new_with_pos_and_vel(pos, vel)
+    })
+}
+
+// Deterministic spiral of bodies with varying masses
+fn spawn_cluster(test_app: &mut TestApp) -> Vec<Entity> {
+    (0..64)
+        .map(|i| {
+            let angle = i as f32 * 2.399_963;
+            let radius = 0.5 + (i as f32).sqrt();
+            let pos = Vec2::from_angle(angle) * radius;
+            spawn_body(test_app, pos, Vec2::ZERO, 1. + (i % 5) as f32)
+        })
+        .collect()
+}
+
+#[test]
+fn two_body_circular_orbit() {
+    let constant = 1.;
+    let mut test_app = gravitation_app(MutualGravitation {
+        constant,
+        softening: 0.,
+        barnes_hut_theta: None,
+    });
+    // A light satellite around a heavy body on a circular orbit of radius 5
+    let (mass, radius) = (100., 5.);
+    let speed = (constant * mass / radius).sqrt();
+    let sun = spawn_body(&mut test_app, Vec2::ZERO, Vec2::ZERO, mass);
+    let satellite = spawn_body(
+        &mut test_app,
+        Vec2::new(radius, 0.),
+        Vec2::new(0., speed),
+        1e-3,
+    );
+
+    let period = TAU * radius / speed;
+    let ticks = (period / DELTA_TIME).round() as usize;
+    for _ in 0..ticks {
+        test_app.step(1);
+        let distance = test_app.pos(satellite).distance(test_app.pos(sun));
+        assert!((distance - radius).abs() < 0.05, "{distance}");
+    }
+    // Back where it started after one period
+    assert!(test_app.pos(satellite).distance(Vec2::new(radius, 0.)) < 0.2);
+}
+
+#[test]
+fn direct_summation_conserves_momentum() {
+    let mut test_app = gravitation_app(MutualGravitation {
+        constant: 1.,
+        softening: 0.1,
+        barnes_hut_theta: None,
+    });
+    let bodies = spawn_cluster(&mut test_app);
+
+    let initial = test_app.momentum(&bodies);
+    test_app.step(60);
+    let momentum = test_app.momentum(&bodies);
+    assert!(momentum.distance(initial) < 1e-2, "{momentum} vs {initial}");
+}
+
+#[test]
+fn barnes_hut_approximates_direct_summation() {
+    let gravitation = |barnes_hut_theta| MutualGravitation {
+        constant: 1.,
+        softening: 0.1,
+        barnes_hut_theta,
+    };
+    let mut direct = gravitation_app(gravitation(None));
+    let mut barnes_hut = gravitation_app(gravitation(Some(0.5)));
+    let direct_bodies = spawn_cluster(&mut direct);
+    let barnes_hut_bodies = spawn_cluster(&mut barnes_hut);
+    direct.step(1);
+    barnes_hut.step(1);
+
+    // Both started at rest, so the velocities are the accelerations scaled by the time step.
+    // Errors are relative to the average so bodies with a near zero net pull don't dominate.
+    let average = direct_bodies
+        .iter()
+        .map(|&a| direct.vel(a).length())
+        .sum::<f32>()
+        / direct_bodies.len() as f32;
+    for (&a, &b) in direct_bodies.iter().zip(&barnes_hut_bodies) {
+        let error = barnes_hut.vel(b).distance(direct.vel(a)) / average;
+        assert!(error < 0.05, "relative error {error}");
+    }
+}
+
+#[test]
+fn softening_keeps_coincident_bodies_finite() {
+    for barnes_hut_theta in [None, Some(0.5)] {
+        let mut test_app = gravitation_app(MutualGravitation {
+            constant: 1.,
+            softening: 0.1,
+            barnes_hut_theta,
+        });
+        let a = spawn_body(&mut test_app, Vec2::ONE, Vec2::ZERO, 1.);
+        let b = spawn_body(&mut test_app, Vec2::ONE, Vec2::ZERO, 1.);
+        test_app.step(10);
+        assert!(test_app.pos(a).is_finite() && test_app.pos(b).is_finite());
+    }
+}
+
+#[test]
+fn no_softening_stays_finite() {
+    for barnes_hut_theta in [None, Some(0.5)] {
+        let mut test_app = gravitation_app(MutualGravitation {
+            constant: 1.,
+            softening: 0.,
+            barnes_hut_theta,
+        });
+        let mut bodies = spawn_cluster(&mut test_app);
+        bodies.push(spawn_body(&mut test_app, Vec2::ONE, Vec2::ZERO, 1.));
+        bodies.push(spawn_body(&mut test_app, Vec2::ONE, Vec2::ZERO, 1.));
+        test_app.step(10);
+        for body in bodies {
+            assert!(test_app.pos(body).is_finite());
+        }
+    }
+}
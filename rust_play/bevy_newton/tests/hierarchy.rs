@@ -0,0 +1,144 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+fn translation(test_app: &TestApp, entity: Entity) -> Vec3 {
+    test_app
+        .app
+        .world()
+        .get::<Transform>(entity)
+        .unwrap()
+        .translation
+}
+
+fn global_translation(test_app: &TestApp, entity: Entity) -> Vec3 {
+    test_app
+        .app
+        .world()
+        .get::<GlobalTransform>(entity)
+        .unwrap()
+        .translation()
+}
+
+#[test]
+fn child_body_is_synced_in_parent_space() {
+    let mut test_app = TestApp::without_gravity();
+    let parent = test_app.spawn(Transform::from_xyz(10., 5., 0.));
+    let child = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::new(12., 5.), Vec2::ZERO),
+        Transform::from_xyz(0., 0., 1.),
+        ChildOf(parent),
+    ));
+    test_app.step(2);
+
+    assert!(translation(&test_app, child).distance(Vec3::new(2., 0., 1.)) < 1e-4);
+    assert!(global_translation(&test_app, child).distance(Vec3::new(12., 5., 1.)) < 1e-4);
+}
+
+#[test]
+fn child_body_under_rotated_and_scaled_parent() {
+    let mut test_app = TestApp::without_gravity();
+    let parent = test_app.spawn(
+        Transform::from_xyz(1., 1., 0.)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2))
+            .with_scale(Vec3::splat(2.)),
+    );
+    let child = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::new(1., 5.), Vec2::ZERO),
+        Transform::default(),
+        ChildOf(parent),
+    ));
+    test_app.step(2);
+
+    assert!(translation(&test_app, child).distance(Vec3::new(2., 0., 0.)) < 1e-4);
+    let world = global_translation(&test_app, child).truncate();
+    assert!(world.distance(Vec2::new(1., 5.)) < 1e-4);
+}
+
+#[test]
+fn child_colliders_collide_with_their_body() {
+    let mut test_app = TestApp::without_gravity();
+    // The body itself has no collider, only a child offset to its right
+    let body = test_app.spawn(Particle::new_with_pos_and_vel(Vec2::ZERO, Vec2::ZERO));
+    test_app.app.world_mut().entity_mut(body).with_child((
+        CircleCollider { radius: 0.5 },
+        Transform::from_xyz(2., 0., 0.),
+    ));
+    let ball = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::new(5., 0.), Vec2::new(-3., 0.)),
+        CircleCollider { radius: 0.5 },
+    ));
+    let bodies = [body, ball];
+
+    let initial = test_app.momentum(&bodies);
+    test_app.step(120);
+
+    // The ball stopped at the child collider instead of passing through
+    assert!(test_app.pos(ball).x >= test_app.pos(body).x + 3. - 1e-4);
+    assert!(test_app.vel(body).x < 0.);
+    assert!(test_app.momentum(&bodies).distance(initial) < 1e-3);
+}
+
+#[test]
+fn child_colliders_turn_with_their_body() {
+    let mut test_app = TestApp::without_gravity();
+    // Turned a quarter, so the child at (0, -2) sits 2 to the body's right
+    let body = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::ZERO, Vec2::ZERO),
+        Transform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+    ));
+    test_app.app.world_mut().entity_mut(body).with_child((
+        CircleCollider { radius: 0.5 },
+        Transform::from_xyz(0., -2., 0.),
+    ));
+    let ball = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::new(5., 0.), Vec2::new(-3., 0.)),
+        CircleCollider { radius: 0.5 },
+    ));
+    test_app.step(120);
+
+    assert!(test_app.pos(ball).x >= test_app.pos(body).x + 3. - 1e-4);
+    assert!(test_app.vel(body).x < 0.);
+}
+
+#[test]
+fn scaled_body_keeps_its_child_colliders_unscaled() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::ZERO, Vec2::ZERO),
+        Density(1.),
+        Transform::from_scale(Vec3::splat(2.)),
+    ));
+    test_app.app.world_mut().entity_mut(body).with_child((
+        CircleCollider { radius: 0.5 },
+        Transform::from_xyz(2., 0., 0.),
+    ));
+    let ball = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::new(5., 0.), Vec2::new(-3., 0.)),
+        CircleCollider { radius: 0.5 },
+    ));
+    test_app.step(120);
+
+    // Contacts and the center of mass both use the offset and radius as given
+    let center_of_mass = test_app.app.world().get::<CenterOfMass>(body).unwrap().0;
+    assert!(center_of_mass.distance(Vec2::new(2., 0.)) < 1e-4);
+    let gap = test_app.pos(ball).x - test_app.pos(body).x;
+    assert!(gap >= 3. - 1e-4 && gap < 3.5);
+}
+
+#[test]
+fn colliders_of_the_same_body_do_not_collide() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::ZERO, Vec2::ZERO),
+        CircleCollider { radius: 1. },
+    ));
+    test_app.app.world_mut().entity_mut(body).with_child((
+        CircleCollider { radius: 1. },
+        Transform::from_xyz(0.5, 0., 0.),
+    ));
+    test_app.step(10);
+    assert_eq!(test_app.pos(body), Vec2::ZERO);
+}
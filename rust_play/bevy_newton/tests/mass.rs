@@ -0,0 +1,126 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{a} vs {b}");
+}
+
+#[test]
+fn mass_is_computed_from_circle_density() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((
+        Particle::default(),
+        CircleCollider { radius: 2. },
+        Density(3.),
+    ));
+    test_app.step(1);
+
+    let mass = 3. * PI * 4.;
+    assert_close(test_app.mass(body), mass);
+    assert_close(
+        test_app.app.world().get::<Inertia>(body).unwrap().0,
+        0.5 * mass * 4.,
+    );
+}
+
+#[test]
+fn bodies_without_density_keep_their_mass() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((
+        Particle {
+            mass: Mass(5.),
+            ..default()
+        },
+        CircleCollider { radius: 2. },
+    ));
+    test_app.step(1);
+    assert_eq!(test_app.mass(body), 5.);
+}
+
+#[test]
+fn compound_collider_from_children() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((Particle::default(), Density(1.)));
+    test_app
+        .app
+        .world_mut()
+        .entity_mut(body)
+        .with_children(|parent| {
+            parent.spawn((
+                CircleCollider { radius: 1. },
+                Transform::from_xyz(-1., 0., 0.),
+            ));
+            parent.spawn((
+                CircleCollider { radius: 1. },
+                Transform::from_xyz(2., 0., 0.),
+                Density(2.),
+            ));
+        });
+    test_app.step(1);
+
+    let properties = CircleCollider { radius: 1. }
+        .mass_properties(1.)
+        .translated(Vec2::new(-1., 0.))
+        .combine(
+            CircleCollider { radius: 1. }
+                .mass_properties(2.)
+                .translated(Vec2::new(2., 0.)),
+        );
+    assert_close(test_app.mass(body), 3. * PI);
+    assert_close(properties.mass, 3. * PI);
+    // Center of mass is pulled towards the denser child
+    let center_of_mass = test_app.app.world().get::<CenterOfMass>(body).unwrap().0;
+    assert_close(center_of_mass.x, 1.);
+    // Two unit discs of mass PI and 2 PI, 1 and 2 units away from the shared center
+    let inertia = 0.5 * PI + 0.5 * 2. * PI + PI * 4. + 2. * PI * 1.;
+    assert_close(
+        test_app.app.world().get::<Inertia>(body).unwrap().0,
+        inertia,
+    );
+    assert_close(properties.inertia, inertia);
+}
+
+#[test]
+fn changing_a_collider_updates_mass() {
+    let mut test_app = TestApp::without_gravity();
+    let body = test_app.spawn((
+        Particle::default(),
+        CircleCollider { radius: 1. },
+        Density(1.),
+    ));
+    let child = test_app.spawn((CircleCollider { radius: 1. }, Transform::default()));
+    test_app.app.world_mut().entity_mut(body).add_child(child);
+    test_app.step(1);
+    assert_close(test_app.mass(body), 2. * PI);
+
+    test_app
+        .app
+        .world_mut()
+        .get_mut::<CircleCollider>(body)
+        .unwrap()
+        .radius = 2.;
+    test_app.step(1);
+    assert_close(test_app.mass(body), 5. * PI);
+
+    test_app
+        .app
+        .world_mut()
+        .get_mut::<CircleCollider>(child)
+        .unwrap()
+        .radius = 3.;
+    test_app.step(1);
+    assert_close(test_app.mass(body), 13. * PI);
+
+    test_app
+        .app
+        .world_mut()
+        .entity_mut(child)
+        .remove::<CircleCollider>();
+    test_app.step(1);
+    assert_close(test_app.mass(body), 4. * PI);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+#[test]
+fn each_step_is_one_physics_tick() {
+    let mut test_app = TestApp::new();
+    test_app.step(120);
+    assert_eq!(test_app.elapsed_ticks(), 120);
+}
+
+#[test]
+fn particle_at_rest_without_gravity_stays_put() {
+    let mut test_app = TestApp::without_gravity();
+    let particle = test_app.spawn(Particle::new_with_pos_and_vel(
+        Vec2::new(3., 4.),
+        Vec2::ZERO,
+    ));
+    test_app.step(600);
+    assert_eq!(test_app.pos(particle), Vec2::new(3., 4.));
+}
+
+#[test]
+fn projectile_follows_analytic_parabola() {
+    let mut test_app = TestApp::new();
+    let g = test_app.app.world().resource::<Gravity>().0;
+    let p0 = Vec2::new(-5., 1.);
+    let v0 = Vec2::new(4., 12.);
+    let particle = test_app.spawn(Particle::new_with_pos_and_vel(p0, v0));
+
+    for tick in 1..=180 {
+        test_app.step(1);
+        let t = tick as f32 * DELTA_TIME;
+        let analytic = p0 + v0 * t + 0.5 * g * t * t;
+        // Symplectic Euler lags the exact solution by g * dt * t / 2
+        let bound = 0.5 * g.length() * DELTA_TIME * t + 1e-3;
+        let error = test_app.pos(particle).distance(analytic);
+        assert!(error <= bound, "tick {tick}: error {error} > {bound}");
+    }
+}
+
+#[test]
+fn projectile_apex_matches_analytic_height() {
+    let mut test_app = TestApp::new();
+    let g = test_app.app.world().resource::<Gravity>().0.y;
+    let v0 = Vec2::new(0., 10.);
+    let particle = test_app.spawn(Particle::new_with_pos_and_vel(Vec2::ZERO, v0));
+
+    let mut apex = f32::MIN;
+    for _ in 0..240 {
+        test_app.step(1);
+        apex = apex.max(test_app.pos(particle).y);
+    }
+    let analytic_apex = -v0.y * v0.y / (2. * g);
+    assert!(
+        (apex - analytic_apex).abs() < 0.1,
+        "{apex} vs {analytic_apex}"
+    );
+}
+
+#[test]
+fn energy_drift_is_bounded() {
+    let mut test_app = TestApp::new();
+    let g = test_app.app.world().resource::<Gravity>().0;
+    let particle = test_app.spawn(Particle::new_with_pos_and_vel(
+        Vec2::ZERO,
+        Vec2::new(3., 20.),
+    ));
+
+    // Symplectic Euler conserves the energy with the potential taken half a step back
+    let energy = |test_app: &TestApp| {
+        let vel = test_app.vel(particle);
+        let half_step_pos = test_app.pos(particle) - 0.5 * vel * DELTA_TIME;
+        0.5 * vel.length_squared() - g.dot(half_step_pos)
+    };
+
+    test_app.step(1);
+    let initial = energy(&test_app);
+    for _ in 0..300 {
+        test_app.step(1);
+        let drift = (energy(&test_app) - initial).abs() / initial;
+        assert!(drift < 1e-3, "relative energy drift {drift}");
+    }
+}
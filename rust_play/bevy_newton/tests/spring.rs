@@ -0,0 +1,67 @@
+mod common;
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_newton::*;
+use common::TestApp;
+
+#[test]
+fn damped_spring_settles_on_target() {
+    let mut test_app = TestApp::without_gravity();
+    let target = Vec2::new(3., -2.);
+    let body = test_app.spawn((
+        Particle::new_with_pos_and_vel(Vec2::ZERO, Vec2::new(0., 5.)),
+        SpringConstraint::new(target),
+    ));
+    test_app.step(600);
+    assert!(test_app.pos(body).distance(target) < 1e-3);
+    assert!(test_app.vel(body).length() < 1e-2);
+}
+
+#[test]
+fn undamped_spring_oscillates_at_natural_frequency() {
+    let mut test_app = TestApp::without_gravity();
+    let (stiffness, mass) = (40., 2.);
+    let body = test_app.spawn((
+        Particle {
+            mass: Mass(mass),
+            ..Particle::new_with_pos_and_vel(Vec2::new(1., 0.), Vec2::ZERO)
+        },
+        SpringConstraint {
+            target: Vec2::ZERO,
+            stiffness,
+            damping: 0.,
+        },
+    ));
+
+    // Count the ticks until the body has passed the target twice, i.e. after one full period
+    let mut crossings = Vec::new();
+    let mut last_x = test_app.pos(body).x;
+    for tick in 1..=600 {
+        test_app.step(1);
+        let x = test_app.pos(body).x;
+        if last_x.signum() != x.signum() {
+            crossings.push(tick);
+        }
+        last_x = x;
+    }
+    let period = (crossings[2] - crossings[0]) as f32 * DELTA_TIME;
+    let analytic = TAU * (mass / stiffness).sqrt();
+    assert!(
+        (period - analytic).abs() < 2. * DELTA_TIME,
+        "{period} vs {analytic}"
+    );
+}
+
+#[test]
+fn spring_holds_body_against_gravity() {
+    let mut test_app = TestApp::new();
+    let g = test_app.app.world().resource::<Gravity>().0;
+    let spring = SpringConstraint::new(Vec2::ZERO);
+    let stiffness = spring.stiffness;
+    let body = test_app.spawn((Particle::default(), spring));
+    test_app.step(600);
+    // Hangs where the spring force cancels the weight
+    assert!(test_app.pos(body).distance(g / stiffness) < 1e-3);
+}
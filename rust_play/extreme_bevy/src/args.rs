@@ -6,6 +6,63 @@ pub struct Args {
     // runs the game in synctest mode
     #[clap(long)]
     pub synctest: bool,
-    #[clap(long)]
+    // watches the match with a free camera instead of playing
+    #[clap(long, conflicts_with = "synctest")]
+    pub spectate: bool,
+    // matchbox room, any query is replaced by one built from --players and --spectators
+    #[clap(long, default_value = "ws://127.0.0.1:3536/extreme_bevy")]
     pub room_url: String,
+    // players per match, every peer has to pass the same value
+    #[clap(long, default_value_t = 2, value_parser = clap::value_parser!(u16).range(2..))]
+    pub players: u16,
+    // spectators per match, every peer has to pass the same value
+    #[clap(long, default_value_t = 0)]
+    pub spectators: u16,
+    // rounds needed to win the match, peers refuse to play with a different value
+    #[clap(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+    pub first_to: u32,
+}
+
+impl Args {
+    pub fn num_players(&self) -> usize {
+        self.players as usize
+    }
+
+    /// Everyone in the match, players and spectators alike
+    pub fn num_peers(&self) -> usize {
+        self.num_players() + self.spectators as usize
+    }
+
+    /// The room URL, asking matchbox to group peers into matches of `players` and `spectators`
+    pub fn room(&self) -> String {
+        let (room, query) = self
+            .room_url
+            .split_once('?')
+            .unwrap_or((&self.room_url, ""));
+        if !query.is_empty() {
+            warn!(
+                "Ignoring `?{query}` in --room-url, the room size comes from --players and --spectators"
+            );
+        }
+        format!("{room}?next={}", self.num_peers())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn room_replaces_the_query() {
+        let args = Args::parse_from([
+            "extreme_bevy",
+            "--room-url",
+            "ws://example.com/room?next=2",
+            "--players",
+            "3",
+            "--spectators",
+            "1",
+        ]);
+        assert_eq!(args.room(), "ws://example.com/room?next=4");
+    }
 }
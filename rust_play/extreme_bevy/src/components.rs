@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct Player {
     pub handle: usize,
 }
@@ -12,3 +12,7 @@ pub struct Bullet;
 
 #[derive(Component, Clone, Copy)]
 pub struct MoveDir(pub Vec2);
+
+/// Frames left before the entity despawns
+#[derive(Component, Clone, Copy)]
+pub struct Lifetime(pub u32);
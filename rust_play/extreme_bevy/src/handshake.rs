@@ -0,0 +1,105 @@
+use crate::args::Args;
+
+const ROLE_PLAYER: u8 = 0;
+const ROLE_SPECTATOR: u8 = 1;
+
+/// What every peer tells the others once connected, so everyone knows who plays, and can check
+/// they all agree on the rules before the match starts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hello {
+    pub spectating: bool,
+    pub players: u16,
+    pub first_to: u32,
+}
+
+impl Hello {
+    pub fn new(args: &Args) -> Self {
+        Self {
+            spectating: args.spectate,
+            players: args.players,
+            first_to: args.first_to,
+        }
+    }
+
+    pub fn encode(&self) -> Box<[u8]> {
+        let role = if self.spectating {
+            ROLE_SPECTATOR
+        } else {
+            ROLE_PLAYER
+        };
+        [role]
+            .into_iter()
+            .chain(self.players.to_le_bytes())
+            .chain(self.first_to.to_le_bytes())
+            .collect()
+    }
+
+    /// `None` for anything malformed, as it comes straight off the network
+    pub fn decode(packet: &[u8]) -> Option<Self> {
+        let (&role, rest) = packet.split_first()?;
+        let (players, first_to) = rest.split_at_checked(2)?;
+        let spectating = match role {
+            ROLE_PLAYER => false,
+            ROLE_SPECTATOR => true,
+            _ => return None,
+        };
+        Some(Self {
+            spectating,
+            players: u16::from_le_bytes(players.try_into().ok()?),
+            first_to: u32::from_le_bytes(first_to.try_into().ok()?),
+        })
+    }
+
+    /// Whether a match with the other peer would play out the same on both ends
+    pub fn agrees_with(&self, other: &Self) -> bool {
+        self.players == other.players && self.first_to == other.first_to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: Hello = Hello {
+        spectating: true,
+        players: 3,
+        first_to: 7,
+    };
+
+    #[test]
+    fn hello_survives_the_round_trip() {
+        assert_eq!(Hello::decode(&HELLO.encode()), Some(HELLO));
+        let player = Hello {
+            spectating: false,
+            ..HELLO
+        };
+        assert_eq!(Hello::decode(&player.encode()), Some(player));
+    }
+
+    #[test]
+    fn malformed_hellos_are_refused() {
+        let packet = HELLO.encode();
+        for len in 0..packet.len() {
+            assert_eq!(Hello::decode(&packet[..len]), None);
+        }
+        assert_eq!(Hello::decode(&[packet.as_ref(), &[0]].concat()), None);
+        assert_eq!(Hello::decode(&[7, 3, 0, 7, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn peers_agree_only_on_the_same_rules() {
+        let playing = Hello {
+            spectating: false,
+            ..HELLO
+        };
+        assert!(HELLO.agrees_with(&playing));
+        assert!(!HELLO.agrees_with(&Hello {
+            players: 2,
+            ..HELLO
+        }));
+        assert!(!HELLO.agrees_with(&Hello {
+            first_to: 5,
+            ..HELLO
+        }));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::Config;
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{platform::collections::HashMap, prelude::*};
 use bevy_ggrs::{LocalInputs, LocalPlayers};
 
 const INPUT_UP: u8 = 1 << 0;
@@ -15,28 +15,32 @@ pub fn read_local_inputs(
 ) {
     let mut local_inputs = HashMap::new();
     for handle in &local_players.0 {
-        let mut input = 0u8;
-        if keys.any_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
-            input |= INPUT_UP;
-        }
-        if keys.any_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
-            input |= INPUT_DOWN;
-        }
-        if keys.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
-            input |= INPUT_LEFT;
-        }
-        if keys.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
-            input |= INPUT_RIGHT;
-        }
-        if keys.any_pressed([KeyCode::Space, KeyCode::Enter]) {
-            input |= INPUT_FIRE;
-        }
-        local_inputs.insert(*handle, input);
+        local_inputs.insert(*handle, keyboard_input(&keys));
     }
 
     commands.insert_resource(LocalInputs::<Config>(local_inputs));
 }
 
+pub fn keyboard_input(keys: &ButtonInput<KeyCode>) -> u8 {
+    let mut input = 0u8;
+    if keys.any_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        input |= INPUT_UP;
+    }
+    if keys.any_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        input |= INPUT_DOWN;
+    }
+    if keys.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        input |= INPUT_LEFT;
+    }
+    if keys.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        input |= INPUT_RIGHT;
+    }
+    if keys.any_pressed([KeyCode::Space, KeyCode::Enter]) {
+        input |= INPUT_FIRE;
+    }
+    input
+}
+
 pub fn direction(input: u8) -> Vec2 {
     let mut direction = Vec2::ZERO;
     if input & INPUT_UP != 0 {
@@ -1,25 +1,36 @@
 mod args;
 mod components;
+mod handshake;
 mod input;
+mod round;
 use bevy::{
-    math::VectorSpace, prelude::*, render::camera::ScalingMode, tasks::IoTaskPool, utils::HashMap,
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    platform::collections::HashMap,
+    prelude::*,
+    render::camera::ScalingMode,
 };
 use bevy_asset_loader::prelude::*;
 use bevy_ggrs::*;
-use bevy_matchbox::{
-    matchbox_socket::{PeerId, WebRtcSocket},
-    prelude::*,
-};
+use bevy_matchbox::prelude::*;
 use clap::Parser;
 
 use args::Args;
 use components::*;
+use handshake::Hello;
 use input::*;
+use round::*;
 
 const MAP_SIZE: u32 = 41;
 const GRID_WIDTH: f32 = 0.05;
 const PLAYER_RADIUS: f32 = 0.5;
 const BULLET_RADIUS: f32 = 0.025;
+const CAMERA_SPEED: f32 = 10.;
+
+// GGRS traffic goes over the unreliable channel, the handshake over the reliable one
+const GGRS_CHANNEL: usize = 0;
+const HELLO_CHANNEL: usize = 1;
+// Counted in rollback frames rather than seconds, so peers agree on the exact frame
+const BULLET_LIFETIME: u32 = 60 * 2;
 
 #[derive(AssetCollection, Resource)]
 struct ImageAssets {
@@ -42,88 +53,113 @@ enum GameState {
 type Config = bevy_ggrs::GgrsConfig<u8, PeerId>;
 
 fn setup(mut commands: Commands) {
-    let mut camera_bundle = Camera2dBundle::default();
-    camera_bundle.projection.scaling_mode = ScalingMode::FixedVertical(10.);
-    commands.spawn(camera_bundle);
+    commands.spawn((
+        Camera2d,
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 10.,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
 
     // Horizontal lines
     for i in 0..=MAP_SIZE {
-        commands.spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(
-                0.,
-                i as f32 - MAP_SIZE as f32 / 2.,
-                0.,
-            )),
-            sprite: Sprite {
+        commands.spawn((
+            Sprite {
                 color: Color::srgb(0.27, 0.27, 0.27),
                 custom_size: Some(Vec2::new(MAP_SIZE as f32, GRID_WIDTH)),
                 ..default()
             },
-            ..default()
-        });
+            Transform::from_translation(Vec3::new(0., i as f32 - MAP_SIZE as f32 / 2., 0.)),
+        ));
     }
 
     // Vertical lines
     for i in 0..=MAP_SIZE {
-        commands.spawn(SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(
-                i as f32 - MAP_SIZE as f32 / 2.,
-                0.,
-                0.,
-            )),
-            sprite: Sprite {
+        commands.spawn((
+            Sprite {
                 color: Color::srgb(0.27, 0.27, 0.27),
                 custom_size: Some(Vec2::new(GRID_WIDTH, MAP_SIZE as f32)),
                 ..default()
             },
-            ..default()
-        });
+            Transform::from_translation(Vec3::new(i as f32 - MAP_SIZE as f32 / 2., 0., 0.)),
+        ));
     }
+
+    commands.spawn((
+        ScoreText,
+        Text::default(),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.),
+            width: Val::Percent(100.),
+            ..default()
+        },
+    ));
 }
 
-fn spawn_players(mut commands: Commands) {
-    // player 0
-    commands
-        .spawn((
-            Player { handle: 0 },
-            BulletReady(true),
-            MoveDir(-Vec2::X),
-            SpriteBundle {
-                transform: Transform::from_translation(Vec3::new(-2., 0., 100.)),
-                sprite: Sprite {
-                    color: Color::srgb(0., 0.47, 1.),
-                    custom_size: Some(Vec2::new(1., 1.)),
-                    ..default()
-                },
-                ..default()
-            },
-        ))
-        .add_rollback();
+fn spawn_player(commands: &mut Commands, handle: usize, num_players: usize) {
+    // spread evenly around a circle facing outwards, player 0 on the left as before
+    let turn = handle as f32 / num_players as f32;
+    let move_dir = -Vec2::from_angle(turn * std::f32::consts::TAU);
+    let radius = (num_players as f32).min(MAP_SIZE as f32 / 2. - PLAYER_RADIUS);
+    let pos = move_dir * radius;
+    let color = match handle {
+        0 => Color::srgb(0., 0.47, 1.),
+        1 => Color::srgb(0., 0.4, 0.),
+        _ => Color::hsl((210. + turn * 360.) % 360., 1., 0.3),
+    };
 
-    // player 1
     commands
         .spawn((
-            Player { handle: 1 },
+            Player { handle },
             BulletReady(true),
-            MoveDir(Vec2::X),
-            SpriteBundle {
-                transform: Transform::from_translation(Vec3::new(2., 0., 100.)),
-                sprite: Sprite {
-                    color: Color::srgb(0., 0.4, 0.),
-                    custom_size: Some(Vec2::new(1., 1.)),
-                    ..default()
-                },
+            MoveDir(move_dir),
+            Sprite {
+                color,
+                custom_size: Some(Vec2::new(1., 1.)),
                 ..default()
             },
+            Transform::from_translation(pos.extend(100.)),
         ))
         .add_rollback();
 }
 
 fn start_matchbox_socket(mut commands: Commands, args: Res<Args>) {
-    // let room_url = "ws://box.vitalyr.com/extreme_bevy?next=2";
-    let room_url = args.room_url.clone();
+    // let room_url = "ws://box.vitalyr.com/extreme_bevy";
+    let room_url = args.room();
     info!("connecting to matchbox server: {room_url}");
-    commands.insert_resource(MatchboxSocket::new_ggrs(room_url));
+    let socket = WebRtcSocketBuilder::new(room_url)
+        .add_unreliable_channel()
+        .add_reliable_channel();
+    commands.insert_resource(MatchboxSocket::from(socket));
+}
+
+fn start_synctest_session(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    args: Res<Args>,
+) {
+    info!("Starting synctest session");
+    let num_players = args.num_players();
+
+    let mut session_builder = ggrs::SessionBuilder::<Config>::new().with_num_players(num_players);
+
+    // every player is local, and GGRS rolls back and resimulates each frame to catch desyncs
+    for i in 0..num_players {
+        session_builder = session_builder
+            .add_player(ggrs::PlayerType::Local, i)
+            .expect("failed to add player");
+    }
+
+    let ggrs_session = session_builder
+        .start_synctest_session()
+        .expect("failed to start session");
+
+    commands.insert_resource(bevy_ggrs::Session::SyncTest(ggrs_session));
+    next_state.set(GameState::InGame);
 }
 
 fn move_players(
@@ -141,7 +177,7 @@ fn move_players(
         move_dir.0 = direction;
 
         let move_speed = 7.;
-        let move_delta = (direction * move_speed) * time.delta_seconds();
+        let move_delta = (direction * move_speed) * time.delta_secs();
 
         let old_pos = transform.translation.xy();
         let limit = Vec2::splat(MAP_SIZE as f32 / 2. - 0.5);
@@ -154,38 +190,112 @@ fn move_players(
 
 fn wait_for_players(
     mut commands: Commands,
-    mut socket: ResMut<MatchboxSocket<SingleChannel>>,
+    mut socket: ResMut<MatchboxSocket>,
     mut next_state: ResMut<NextState<GameState>>,
+    args: Res<Args>,
+    mut hellos: Local<HashMap<PeerId, Hello>>,
 ) {
-    if socket.get_channel(0).is_err() {
+    if socket.get_channel(GGRS_CHANNEL).is_err() {
         // we've already started
         return;
     }
 
-    // check for new connections
-    socket.update_peers();
-    let players = socket.players();
+    // check for new connections, and tell them whether we play or watch
+    let hello = Hello::new(&args);
+    for (peer, state) in socket.update_peers() {
+        match state {
+            PeerState::Connected => socket.channel_mut(HELLO_CHANNEL).send(hello.encode(), peer),
+            PeerState::Disconnected => {
+                hellos.remove(&peer);
+            }
+        }
+    }
+    for (peer, packet) in socket.channel_mut(HELLO_CHANNEL).receive() {
+        match Hello::decode(&packet) {
+            Some(theirs) if hello.agrees_with(&theirs) => {
+                hellos.insert(peer, theirs);
+            }
+            theirs => {
+                // the match would desync, so don't start it at all
+                error!("Peer {peer} doesn't agree on the match, ours {hello:?}, theirs {theirs:?}");
+                commands.close_socket();
+                return;
+            }
+        }
+    }
 
-    let num_players = 2;
-    if players.len() < num_players {
-        return; // wait for more players
+    let Some(our_id) = socket.id() else {
+        return;
+    };
+    if hellos.len() + 1 < args.num_peers() {
+        return; // wait for more peers, or for their hellos
     }
 
     info!("All peers have joined, going in-game");
 
-    // create a GGRS P2P session
+    // sorted, so every peer agrees on the player handles and on who hosts the spectators
+    let mut players: Vec<PeerId> = hellos
+        .iter()
+        .filter(|(_, hello)| !hello.spectating)
+        .map(|(peer, _)| *peer)
+        .chain((!args.spectate).then_some(our_id))
+        .collect();
+    players.sort();
+    let mut spectators: Vec<PeerId> = hellos
+        .iter()
+        .filter(|(_, hello)| hello.spectating)
+        .map(|(peer, _)| *peer)
+        .collect();
+    spectators.sort();
+
+    // everyone agreed on the player count, but too many of them may have come to watch
+    let num_players = args.num_players();
+    if players.len() != num_players {
+        error!(
+            "Expected {num_players} players, but {} of {} peers want to play",
+            players.len(),
+            args.num_peers()
+        );
+        commands.close_socket();
+        return;
+    }
+    let host = players[0];
+
+    // create a GGRS session
     let mut session_builder = ggrs::SessionBuilder::<Config>::new()
         .with_num_players(num_players)
         .with_input_delay(2);
 
+    // move the channel out of the socket (required bacause GGRS takes ownership of it)
+    let channel = socket.take_channel(GGRS_CHANNEL).unwrap();
+
+    if args.spectate {
+        // spectator sessions never read local inputs, they only replay the host's confirmed ones
+        let ggrs_session = session_builder.start_spectator_session(host, channel);
+        commands.insert_resource(bevy_ggrs::Session::Spectator(ggrs_session));
+        next_state.set(GameState::InGame);
+        return;
+    }
+
     for (i, player) in players.into_iter().enumerate() {
+        let player_type = if player == our_id {
+            ggrs::PlayerType::Local
+        } else {
+            ggrs::PlayerType::Remote(player)
+        };
         session_builder = session_builder
-            .add_player(player, i)
+            .add_player(player_type, i)
             .expect("failed to add player");
     }
 
-    // move the channel out of the socket (required bacause GGRS takes ownership of it)
-    let channel = socket.take_channel(0).unwrap();
+    // the host broadcasts confirmed inputs to every spectator
+    if host == our_id {
+        for (i, spectator) in spectators.into_iter().enumerate() {
+            session_builder = session_builder
+                .add_player(ggrs::PlayerType::Spectator(spectator), num_players + i)
+                .expect("failed to add spectator");
+        }
+    }
 
     // start the GGRS session
     let ggrs_session = session_builder
@@ -216,6 +326,29 @@ fn camera_follow(
     }
 }
 
+fn free_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera>>,
+) {
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / 20.,
+    };
+
+    for (mut transform, mut projection) in &mut cameras {
+        let Projection::Orthographic(projection) = &mut *projection else {
+            continue;
+        };
+        projection.scale = (projection.scale * 0.9_f32.powf(lines)).clamp(0.5, 4.);
+
+        // pans with the keys players move with, faster when zoomed out
+        let pan = direction(keyboard_input(&keys)) * CAMERA_SPEED * projection.scale;
+        transform.translation += (pan * time.delta_secs()).extend(0.);
+    }
+}
+
 fn fire_bullets(
     mut commands: Commands,
     inputs: Res<PlayerInputs<Config>>,
@@ -231,16 +364,14 @@ fn fire_bullets(
                 .spawn((
                     Bullet,
                     *move_dir,
-                    SpriteBundle {
-                        transform: Transform::from_translation(pos.extend(200.))
-                            .with_rotation(Quat::from_rotation_arc_2d(Vec2::X, move_dir.0)),
-                        texture: images.bullet.clone(),
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::new(0.3, 0.1)),
-                            ..default()
-                        },
+                    Lifetime(BULLET_LIFETIME),
+                    Sprite {
+                        image: images.bullet.clone(),
+                        custom_size: Some(Vec2::new(0.3, 0.1)),
                         ..default()
                     },
+                    Transform::from_translation(pos.extend(200.))
+                        .with_rotation(Quat::from_rotation_arc_2d(Vec2::X, move_dir.0)),
                 ))
                 .add_rollback();
             bullet_ready.0 = false;
@@ -263,30 +394,63 @@ fn reload_bullet(
 fn move_bullet(mut bullets: Query<(&mut Transform, &MoveDir), With<Bullet>>, time: Res<Time>) {
     for (mut transform, move_dir) in &mut bullets {
         let speed = 17.;
-        let delta = move_dir.0 * speed * time.delta_seconds();
+        let delta = move_dir.0 * speed * time.delta_secs();
         transform.translation += delta.extend(0.);
     }
 }
 
 fn kill_player(
     mut commands: Commands,
-    players: Query<(Entity, &Transform), With<Player>>,
-    bullets: Query<&Transform, With<Bullet>>,
+    order: Res<RollbackOrdered>,
+    players: Query<(Entity, &Rollback, &Transform), With<Player>>,
+    bullets: Query<(Entity, &Rollback, &Transform), With<Bullet>>,
 ) {
-    for (player, player_transform) in &players {
-        for bullet_transform in &bullets {
+    // Query order differs between peers and changes with rollbacks, so hits are resolved in
+    // the order the entities joined the rollback world, which every peer agrees on
+    let mut players: Vec<_> = players.iter().collect();
+    players.sort_by_key(|(_, rollback, _)| order.order(**rollback));
+    let mut bullets: Vec<_> = bullets.iter().collect();
+    bullets.sort_by_key(|(_, rollback, _)| order.order(**rollback));
+
+    let mut killed = Vec::new();
+    for (bullet, _, bullet_transform) in bullets {
+        for &(player, _, player_transform) in &players {
+            if killed.contains(&player) {
+                continue;
+            }
+
             let distance = Vec2::distance(
                 player_transform.translation.xy(),
                 bullet_transform.translation.xy(),
             );
 
             if distance < PLAYER_RADIUS + BULLET_RADIUS {
-                commands.entity(player).despawn_recursive();
+                killed.push(player);
+                commands.entity(player).despawn();
+                commands.entity(bullet).despawn();
+                break;
             }
         }
     }
 }
 
+fn despawn_bullets(
+    mut commands: Commands,
+    mut bullets: Query<(Entity, &Transform, &mut Lifetime), With<Bullet>>,
+) {
+    let limit = MAP_SIZE as f32 / 2.;
+    for (bullet, transform, mut lifetime) in &mut bullets {
+        lifetime.0 = lifetime.0.saturating_sub(1);
+
+        let pos = transform.translation.xy();
+        let outside_map = pos.x.abs() > limit || pos.y.abs() > limit;
+
+        if lifetime.0 == 0 || outside_map {
+            commands.entity(bullet).despawn();
+        }
+    }
+}
+
 fn synctest_mode(args: Res<Args>) -> bool {
     args.synctest
 }
@@ -295,6 +459,10 @@ fn p2p_mode(args: Res<Args>) -> bool {
     !args.synctest
 }
 
+fn spectate_mode(args: Res<Args>) -> bool {
+    args.spectate
+}
+
 fn main() {
     let args = Args::parse();
     eprintln!("{args:?}");
@@ -319,21 +487,43 @@ fn main() {
                 .load_collection::<ImageAssets>()
                 .continue_to_state(GameState::Matchmaking),
         )
+        .rollback_component_with_copy::<Player>()
+        .rollback_component_with_copy::<Bullet>()
         .rollback_component_with_copy::<MoveDir>()
         .rollback_component_with_clone::<Transform>()
         .rollback_component_with_copy::<BulletReady>()
+        .rollback_component_with_copy::<Lifetime>()
+        // entities despawned during a rollback come back with only the registered components,
+        // the sprite's required components are inserted along with it
+        .rollback_component_with_clone::<Sprite>()
+        .rollback_resource_with_clone::<Scores>()
+        .rollback_resource_with_copy::<RoundEndTimer>()
         .insert_resource(ClearColor(Color::srgb(0.53, 0.53, 0.53)))
+        .insert_resource(Scores::new(args.num_players()))
         .insert_resource(args)
+        .init_resource::<RoundEndTimer>()
         .add_systems(
             OnEnter(GameState::Matchmaking),
-            (setup, start_matchbox_socket.run_if(p2p_mode)),
+            (
+                setup,
+                start_matchbox_socket.run_if(p2p_mode),
+                start_synctest_session.run_if(synctest_mode),
+            ),
         )
-        .add_systems(OnEnter(GameState::InGame), spawn_players)
         .add_systems(
             Update,
             (
-                wait_for_players.run_if(in_state(GameState::Matchmaking).and_then(p2p_mode)),
-                camera_follow.run_if(in_state(GameState::InGame)),
+                wait_for_players.run_if(
+                    in_state(GameState::Matchmaking)
+                        .and(p2p_mode)
+                        .and(resource_exists::<MatchboxSocket>),
+                ),
+                (
+                    camera_follow.run_if(not(spectate_mode)),
+                    free_camera.run_if(spectate_mode),
+                    update_score_text,
+                )
+                    .run_if(in_state(GameState::InGame)),
             ),
         )
         .add_systems(ReadInputs, read_local_inputs)
@@ -345,6 +535,10 @@ fn main() {
                 fire_bullets.after(move_players).after(reload_bullet),
                 move_bullet.after(fire_bullets),
                 kill_player.after(move_bullet).after(move_players),
+                // after kill_player, so a bullet is never despawned twice
+                despawn_bullets.after(kill_player),
+                check_round_end.after(kill_player),
+                advance_round.after(check_round_end).after(despawn_bullets),
             ),
         )
         .run();
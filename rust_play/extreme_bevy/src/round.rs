@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{args::Args, components::*, spawn_player};
+
+// Counted in rollback frames, like bullet lifetimes
+const ROUND_END_DELAY: u32 = 60 * 2;
+
+/// Rounds won by each player, indexed by handle
+#[derive(Resource, Clone, Debug)]
+pub struct Scores(pub Vec<u32>);
+
+impl Scores {
+    pub fn new(num_players: usize) -> Self {
+        Self(vec![0; num_players])
+    }
+
+    /// The first player to win `first_to` rounds
+    pub fn winner(&self, first_to: u32) -> Option<usize> {
+        self.0.iter().position(|&score| score >= first_to)
+    }
+}
+
+/// Frames until the next round starts, set once the current round is decided
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct RoundEndTimer(pub Option<u32>);
+
+impl Default for RoundEndTimer {
+    // the first round starts on the first rollback frame, like any other round
+    fn default() -> Self {
+        Self(Some(0))
+    }
+}
+
+#[derive(Component)]
+pub struct ScoreText;
+
+pub fn check_round_end(
+    mut round_end: ResMut<RoundEndTimer>,
+    mut scores: ResMut<Scores>,
+    players: Query<&Player>,
+) {
+    if round_end.0.is_some() {
+        return;
+    }
+
+    // the last player standing takes the round, nobody does when the last ones trade hits
+    let mut alive = players.iter();
+    match (alive.next(), alive.next()) {
+        (Some(_), Some(_)) => return,
+        (Some(winner), None) => scores.0[winner.handle] += 1,
+        (None, _) => {}
+    }
+
+    round_end.0 = Some(ROUND_END_DELAY);
+}
+
+pub fn advance_round(
+    mut commands: Commands,
+    mut round_end: ResMut<RoundEndTimer>,
+    scores: Res<Scores>,
+    args: Res<Args>,
+    players: Query<Entity, With<Player>>,
+    bullets: Query<Entity, With<Bullet>>,
+) {
+    let Some(frames_left) = round_end.0 else {
+        return;
+    };
+
+    if frames_left > 0 {
+        round_end.0 = Some(frames_left - 1);
+        return;
+    }
+
+    // the match is over, leave the final round as it ended
+    if scores.winner(args.first_to).is_some() {
+        return;
+    }
+
+    for entity in players.iter().chain(&bullets) {
+        commands.entity(entity).despawn();
+    }
+    let num_players = scores.0.len();
+    for handle in 0..num_players {
+        spawn_player(&mut commands, handle, num_players);
+    }
+    round_end.0 = None;
+}
+
+pub fn update_score_text(
+    scores: Res<Scores>,
+    args: Res<Args>,
+    mut texts: Query<&mut Text, With<ScoreText>>,
+) {
+    let mut text = scores
+        .0
+        .iter()
+        .enumerate()
+        .map(|(handle, score)| format!("P{}: {score}", handle + 1))
+        .collect::<Vec<_>>()
+        .join("   ");
+    if let Some(winner) = scores.winner(args.first_to) {
+        text += &format!("\nPlayer {} wins!", winner + 1);
+    }
+
+    for mut score_text in &mut texts {
+        if score_text.0 != text {
+            score_text.0.clone_from(&text);
+        }
+    }
+}
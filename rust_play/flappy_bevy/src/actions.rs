@@ -0,0 +1,108 @@
+use std::{collections::HashMap, fs};
+
+use bevy::{input::InputSystems, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::BINDINGS_PATH;
+
+/// What the player wants to do, whichever device they use to say it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Also starts a new run
+    Flap,
+    Pause,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// On any connected gamepad
+    Gamepad(GamepadButton),
+    /// A finger anywhere on the screen
+    Touch,
+}
+
+/// Which inputs trigger each action, loaded from [`BINDINGS_PATH`] if it exists, e.g.
+/// `(actions: {Flap: [Key(Space), Mouse(Left), Touch, Gamepad(South)]})`
+#[derive(Resource, Serialize, Deserialize, Debug)]
+pub struct InputBindings {
+    pub actions: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            actions: HashMap::from([
+                (
+                    Action::Flap,
+                    vec![
+                        Binding::Key(KeyCode::Space),
+                        Binding::Mouse(MouseButton::Left),
+                        Binding::Touch,
+                        Binding::Gamepad(GamepadButton::South),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![
+                        Binding::Key(KeyCode::Escape),
+                        Binding::Gamepad(GamepadButton::Start),
+                    ],
+                ),
+            ]),
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn load(path: &str) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        let mut bindings: Self = ron::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Ignoring unreadable input bindings in {path}: {err}");
+            Self::default()
+        });
+        // Actions added since the file was written keep their default bindings
+        for (action, default_bindings) in Self::default().actions {
+            bindings.actions.entry(action).or_insert(default_bindings);
+        }
+        bindings
+    }
+}
+
+// An action is held while any of its bindings is, so `ButtonInput` can tell when it's just pressed
+fn update_actions(
+    bindings: Res<InputBindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<&Gamepad>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    actions.clear();
+    for (&action, action_bindings) in &bindings.actions {
+        let pressed = action_bindings.iter().any(|binding| match *binding {
+            Binding::Key(key) => keyboard.pressed(key),
+            Binding::Mouse(button) => mouse.pressed(button),
+            Binding::Gamepad(button) => gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+            Binding::Touch => touches.iter().next().is_some(),
+        });
+        if pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load(BINDINGS_PATH))
+            .init_resource::<ButtonInput<Action>>()
+            .add_systems(PreUpdate, update_actions.after(InputSystems));
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use chrono::Datelike;
+use clap::Parser;
+
+#[derive(Parser, Resource, Debug, Clone)]
+pub struct Args {
+    // seeds every run with this instead of a random seed, so the pipes come out the same
+    #[clap(long)]
+    pub seed: Option<u64>,
+    // seeds every run with today's (UTC) seed, shared by everyone playing the daily challenge
+    #[clap(long, conflicts_with = "seed")]
+    pub daily: bool,
+    // evolves AI brains for this many generations without opening a window, then exits
+    #[clap(long, value_name = "GENERATIONS")]
+    pub train: Option<u32>,
+    // lets the best trained brain fly the bird, flapping still starts a run
+    #[clap(long, conflicts_with = "train")]
+    pub autopilot: bool,
+    // starts with the sounds muted, M toggles them in game
+    #[clap(long)]
+    pub mute: bool,
+    // sound volume from 0 to 1, instead of the saved one, - and = change it in game
+    #[clap(long)]
+    pub volume: Option<f32>,
+}
+
+impl Args {
+    pub fn fixed_seed(&self) -> Option<u64> {
+        self.seed.or_else(|| {
+            self.daily
+                .then(|| chrono::Utc::now().num_days_from_ce() as u64)
+        })
+    }
+}
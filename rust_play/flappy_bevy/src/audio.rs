@@ -0,0 +1,110 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    events::{Crashed, Flapped, Scored},
+    highscore::NameEntry,
+    settings::Settings,
+};
+
+// Longer than any of the sounds, see `despawn_finished_sounds`
+const SOUND_LIFETIME_SECONDS: f32 = 2.;
+pub const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Resource)]
+struct Sounds {
+    wing: Handle<AudioSource>,
+    point: Handle<AudioSource>,
+    hit: Handle<AudioSource>,
+}
+
+#[derive(Component)]
+struct Sound(Timer);
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Sounds {
+        wing: asset_server.load("audio/wing.ogg"),
+        point: asset_server.load("audio/point.ogg"),
+        hit: asset_server.load("audio/hit.ogg"),
+    });
+}
+
+fn play(commands: &mut Commands, settings: &Settings, sound: &Handle<AudioSource>, speed: f32) {
+    if settings.muted {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer::new(sound.clone()),
+        PlaybackSettings::DESPAWN
+            .with_volume(Volume::Linear(settings.volume))
+            .with_speed(speed),
+        Sound(Timer::from_seconds(SOUND_LIFETIME_SECONDS, TimerMode::Once)),
+    ));
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    sounds: Res<Sounds>,
+    mut flapped: MessageReader<Flapped>,
+    mut scored: MessageReader<Scored>,
+    mut crashed: MessageReader<Crashed>,
+) {
+    // One sound per kind and frame is enough, even if several were triggered
+    if flapped.read().count() > 0 {
+        play(&mut commands, &settings, &sounds.wing, 1.);
+    }
+    if scored.read().count() > 0 {
+        play(&mut commands, &settings, &sounds.point, 1.);
+    }
+    if let Some(crash) = crashed.read().last() {
+        // There's no separate die sound, a slowed down hit sounds heavy enough for the ground
+        let speed = if crash.into_ground { 0.6 } else { 1. };
+        play(&mut commands, &settings, &sounds.hit, speed);
+    }
+}
+
+// Without an audio device Bevy never plays, and so never despawns, the sounds
+fn despawn_finished_sounds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Sound)>,
+) {
+    for (entity, mut sound) in query.iter_mut() {
+        if sound.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// M toggles mute, - and = turn the volume down and up
+fn adjust_audio(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        settings.muted = !settings.muted;
+    }
+    if keyboard.just_pressed(KeyCode::Minus) {
+        settings.volume = (settings.volume - VOLUME_STEP).max(0.);
+    }
+    if keyboard.just_pressed(KeyCode::Equal) {
+        settings.volume = (settings.volume + VOLUME_STEP).min(1.);
+    }
+}
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        // Headless apps have no use for sounds
+        if !app.is_plugin_added::<bevy::audio::AudioPlugin>() {
+            return;
+        }
+        app.add_systems(Startup, load_sounds).add_systems(
+            Update,
+            (
+                play_sounds,
+                despawn_finished_sounds,
+                // Typing a name for the high score table shouldn't change the volume
+                adjust_audio.run_if(not(resource_exists::<NameEntry>)),
+            ),
+        );
+    }
+}
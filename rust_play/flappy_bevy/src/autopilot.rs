@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::{
+    brain::{Brain, next_pair, senses},
+    collision::pipe_pair_position,
+    components::*,
+    events::Flapped,
+    tuning::Tuning,
+};
+
+/// Flies the bird with a trained brain instead of the keyboard
+#[derive(Resource)]
+pub struct Autopilot(pub Brain);
+
+pub fn autopilot_flap(
+    autopilot: Res<Autopilot>,
+    tuning: Res<Tuning>,
+    mut flapped: MessageWriter<Flapped>,
+    mut birds: Query<(&mut Bird, &Transform)>,
+    pairs: Query<(&Transform, &Children), With<PipePair>>,
+    pipes: Query<(&Transform, Has<UpperPipe>), Without<PipePair>>,
+) {
+    for (mut bird, transform) in birds.iter_mut() {
+        let position = transform.translation.truncate();
+        let next = next_pair(
+            position.x,
+            pairs
+                .iter()
+                .map(|(pair, children)| (pair.translation.x, pipe_pair_position(children, &pipes))),
+        );
+        if let Some((pair_x, gap)) = next
+            && autopilot
+                .0
+                .think(&senses(&tuning, position, bird.velocity, pair_x, gap))
+        {
+            bird.velocity = tuning.flap_velocity;
+            flapped.write(Flapped);
+        }
+    }
+}
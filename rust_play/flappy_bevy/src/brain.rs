@@ -0,0 +1,113 @@
+use std::fs;
+
+use bevy::math::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{BIRD_SIZE, PIPE_SIZE, WINDOW_HEIGHT},
+    tuning::Tuning,
+};
+
+pub const INPUTS: usize = 5;
+pub const HIDDEN: usize = 8;
+// Every neuron has a weight per input plus a bias
+const WEIGHTS: usize = HIDDEN * (INPUTS + 1) + HIDDEN + 1;
+
+/// What a bird sees: where it is, how fast it's moving and where the next gap is
+pub fn senses(
+    tuning: &Tuning,
+    bird: Vec2,
+    velocity: f32,
+    pair_x: f32,
+    (lower_y, upper_y): (f32, f32),
+) -> [f32; INPUTS] {
+    let half_height = WINDOW_HEIGHT / 2.;
+    let gap_bottom = lower_y + PIPE_SIZE.y / 2.;
+    let gap_top = upper_y - PIPE_SIZE.y / 2.;
+    [
+        bird.y / half_height,
+        velocity / tuning.flap_velocity,
+        (pair_x - bird.x) / tuning.pipe_spacing,
+        (gap_bottom - bird.y) / half_height,
+        (gap_top - bird.y) / half_height,
+    ]
+}
+
+/// A tiny neural network deciding when to flap, and the genome the trainer evolves
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Brain {
+    weights: Vec<f32>,
+}
+
+impl Brain {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            weights: (0..WEIGHTS).map(|_| rng.gen_range(-1.0..1.0)).collect(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let brain: Self = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| ron::from_str(&contents).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Failed to load a brain from {path}: {err}"))?;
+        if brain.weights.len() != WEIGHTS {
+            return Err(format!(
+                "{path} has {} weights instead of {WEIGHTS}",
+                brain.weights.len()
+            ));
+        }
+        Ok(brain)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(path, contents).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Failed to save the brain to {path}: {err}"))
+    }
+
+    /// Whether to flap, given the bird's [`senses`]
+    pub fn think(&self, inputs: &[f32; INPUTS]) -> bool {
+        let (hidden_weights, output_weights) = self.weights.split_at(HIDDEN * (INPUTS + 1));
+        let hidden = hidden_weights.chunks(INPUTS + 1).map(|neuron| {
+            let sum: f32 = neuron.iter().zip(inputs).map(|(w, x)| w * x).sum();
+            (sum + neuron[INPUTS]).tanh()
+        });
+        let output: f32 = output_weights.iter().zip(hidden).map(|(w, h)| w * h).sum();
+        output + output_weights[HIDDEN] > 0.
+    }
+
+    /// Each weight comes from either parent
+    pub fn crossover(&self, other: &Self, rng: &mut impl Rng) -> Self {
+        Self {
+            weights: self
+                .weights
+                .iter()
+                .zip(&other.weights)
+                .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
+                .collect(),
+        }
+    }
+
+    /// Nudges every weight with probability `rate` by up to `strength`
+    pub fn mutate(&mut self, rng: &mut impl Rng, rate: f64, strength: f32) {
+        for weight in &mut self.weights {
+            if rng.gen_bool(rate) {
+                *weight += rng.gen_range(-strength..strength);
+            }
+        }
+    }
+}
+
+/// The first pipe pair the bird hasn't cleared yet, out of `(x, (lower_y, upper_y))` pairs
+pub fn next_pair(
+    bird_x: f32,
+    pairs: impl IntoIterator<Item = (f32, (f32, f32))>,
+) -> Option<(f32, (f32, f32))> {
+    pairs
+        .into_iter()
+        .filter(|(x, _)| x + (PIPE_SIZE.x + BIRD_SIZE.x) / 2. > bird_x)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+}
@@ -0,0 +1,93 @@
+use bevy::{
+    camera::{CameraUpdateSystems, ScalingMode, Viewport, visibility::RenderLayers},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+/// Shows the scene, always exactly `WINDOW_WIDTH` by `WINDOW_HEIGHT` world units
+#[derive(Component)]
+pub struct GameCamera;
+
+fn spawn_cameras(mut commands: Commands) {
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: WINDOW_WIDTH,
+                height: WINDOW_HEIGHT,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        // Keeps menus inside the letterboxed scene
+        IsDefaultUiCamera,
+        GameCamera,
+    ));
+    // Renders nothing, only clears the bars around the game camera's viewport
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        RenderLayers::layer(1),
+    ));
+}
+
+/// The largest centered rectangle with the scene's aspect ratio that fits in `window`
+pub fn letterbox(window: UVec2) -> Viewport {
+    let scale = (window.x as f32 / WINDOW_WIDTH).min(window.y as f32 / WINDOW_HEIGHT);
+    let size = (Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT) * scale)
+        .round()
+        .as_uvec2()
+        .max(UVec2::ONE);
+    Viewport {
+        physical_position: (window.saturating_sub(size)) / 2,
+        physical_size: size,
+        ..default()
+    }
+}
+
+// Also runs on a scale factor change, which resizes the window in physical pixels
+fn fit_viewport(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut cameras: Query<&mut Camera, With<GameCamera>>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let viewport = letterbox(window.physical_size());
+    for mut camera in cameras.iter_mut() {
+        camera.viewport = Some(viewport.clone());
+    }
+}
+
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_cameras)
+            .add_systems(PostUpdate, fit_viewport.before(CameraUpdateSystems));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_window_gets_bars_on_the_sides() {
+        let viewport = letterbox(UVec2::new(1920, 512));
+        assert_eq!(viewport.physical_size, UVec2::new(800, 512));
+        assert_eq!(viewport.physical_position, UVec2::new(560, 0));
+    }
+
+    #[test]
+    fn tall_window_gets_bars_above_and_below() {
+        let viewport = letterbox(UVec2::new(400, 1000));
+        assert_eq!(viewport.physical_size, UVec2::new(400, 256));
+        assert_eq!(viewport.physical_position, UVec2::new(0, 372));
+    }
+}
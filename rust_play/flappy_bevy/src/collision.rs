@@ -0,0 +1,174 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    components::*,
+    constants::*,
+    events::Crashed,
+    state::{GameState, Run},
+};
+
+pub fn bird_aabb(center: Vec2) -> Aabb2d {
+    Aabb2d::new(center, BIRD_SIZE / 2.)
+}
+
+pub fn pipe_aabb(center: Vec2) -> Aabb2d {
+    Aabb2d::new(center, PIPE_SIZE / 2.)
+}
+
+pub fn ground_aabb() -> Aabb2d {
+    // Wide enough that the bird can't miss it
+    Aabb2d::new(
+        Vec2::new(0., GROUND_Y),
+        Vec2::new(WINDOW_WIDTH, GROUND_HEIGHT / 2.),
+    )
+}
+
+/// Whether the bird overlaps either pipe of a pair at `pair_x` with the given pipe centers
+pub fn hits_pipe_pair(bird: Vec2, pair_x: f32, lower_y: f32, upper_y: f32) -> bool {
+    let bird = bird_aabb(bird);
+    bird.intersects(&pipe_aabb(Vec2::new(pair_x, lower_y)))
+        || bird.intersects(&pipe_aabb(Vec2::new(pair_x, upper_y)))
+}
+
+pub fn hits_ground(bird: Vec2) -> bool {
+    bird_aabb(bird).intersects(&ground_aabb())
+}
+
+/// Centers of the lower and the upper pipe among a pair's children
+pub fn pipe_pair_position(
+    children: &Children,
+    pipes: &Query<(&Transform, Has<UpperPipe>), Without<PipePair>>,
+) -> (f32, f32) {
+    let (mut lower_y, mut upper_y) = (f32::MIN, f32::MAX);
+    for (pipe, is_upper) in pipes.iter_many(children) {
+        if is_upper {
+            upper_y = pipe.translation.y;
+        } else {
+            lower_y = pipe.translation.y;
+        }
+    }
+    (lower_y, upper_y)
+}
+
+pub fn detect_collisions(
+    birds: Query<&Transform, With<Bird>>,
+    pairs: Query<(&Transform, &Children), With<PipePair>>,
+    pipes: Query<(&Transform, Has<UpperPipe>), Without<PipePair>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut run: ResMut<Run>,
+    mut crashed: MessageWriter<Crashed>,
+) {
+    for bird in birds.iter() {
+        let bird = bird.translation.truncate();
+        let hit_pipe = pairs.iter().any(|(pair, children)| {
+            let (lower_y, upper_y) = pipe_pair_position(children, &pipes);
+            hits_pipe_pair(bird, pair.translation.x, lower_y, upper_y)
+        });
+        let into_ground = !hit_pipe && hits_ground(bird);
+        if hit_pipe || into_ground {
+            next_state.set(GameState::GameOver);
+            run.over = true;
+            crashed.write(Crashed { into_ground });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{tuning::Tuning, utils::random_pipe_position};
+
+    // Vertical range between the top of the lower pipe and the bottom of the upper pipe
+    fn gap(lower_y: f32, upper_y: f32) -> (f32, f32) {
+        (lower_y + PIPE_SIZE.y / 2., upper_y - PIPE_SIZE.y / 2.)
+    }
+
+    #[test]
+    fn gap_fits_the_bird() {
+        let tuning = Tuning::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (lower_y, upper_y) =
+                random_pipe_position(&mut rng, &tuning, tuning.difficulty.min_gap);
+            let (bottom, top) = gap(lower_y, upper_y);
+            assert!(
+                top - bottom > BIRD_SIZE.y,
+                "gap {bottom}..{top} is too small"
+            );
+        }
+    }
+
+    #[test]
+    fn bird_in_the_middle_of_the_gap_passes() {
+        let tuning = Tuning::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (lower_y, upper_y) = random_pipe_position(&mut rng, &tuning, tuning.gap);
+            let (bottom, top) = gap(lower_y, upper_y);
+            let bird = Vec2::new(100., (bottom + top) / 2.);
+            assert!(!hits_pipe_pair(bird, 100., lower_y, upper_y));
+        }
+    }
+
+    #[test]
+    fn bird_touching_a_pipe_collides() {
+        let tuning = Tuning::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (lower_y, upper_y) = random_pipe_position(&mut rng, &tuning, tuning.gap);
+            let (bottom, top) = gap(lower_y, upper_y);
+            let half_height = BIRD_SIZE.y / 2.;
+
+            let on_lower = Vec2::new(100., bottom + half_height - 1.);
+            assert!(hits_pipe_pair(on_lower, 100., lower_y, upper_y));
+            let under_upper = Vec2::new(100., top - half_height + 1.);
+            assert!(hits_pipe_pair(under_upper, 100., lower_y, upper_y));
+            // Clipping the corner of the pipe from the side
+            let corner = Vec2::new(
+                100. - (PIPE_SIZE.x + BIRD_SIZE.x) / 2. + 1.,
+                bottom + half_height - 1.,
+            );
+            assert!(hits_pipe_pair(corner, 100., lower_y, upper_y));
+        }
+    }
+
+    #[test]
+    fn bird_beside_the_pipes_passes() {
+        let tuning = Tuning::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (lower_y, upper_y) = random_pipe_position(&mut rng, &tuning, tuning.gap);
+            let offset = (PIPE_SIZE.x + BIRD_SIZE.x) / 2. + 1.;
+            for y in [lower_y, upper_y] {
+                assert!(!hits_pipe_pair(
+                    Vec2::new(100. - offset, y),
+                    100.,
+                    lower_y,
+                    upper_y
+                ));
+                assert!(!hits_pipe_pair(
+                    Vec2::new(100. + offset, y),
+                    100.,
+                    lower_y,
+                    upper_y
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn bird_hits_ground() {
+        let ground_top = GROUND_Y + GROUND_HEIGHT / 2.;
+        assert!(hits_ground(Vec2::new(0., ground_top)));
+        assert!(!hits_ground(Vec2::new(
+            0.,
+            ground_top + BIRD_SIZE.y / 2. + 1.
+        )));
+    }
+}
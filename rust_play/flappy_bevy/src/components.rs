@@ -10,19 +10,48 @@ pub struct Ground;
 pub struct GameOverText;
 
 #[derive(Component)]
-pub struct CloudBlue;
+pub struct HighScoreText;
+
+/// A cloud drifting across the sky, see `weather`
+#[derive(Component)]
+pub struct CloudBlue {
+    pub speed: f32,
+}
 
 #[derive(Component)]
 pub struct PressSpaceBarText(pub Timer);
 
+/// One digit of the score, 0 being the leftmost of the three
 #[derive(Component)]
-pub struct ScoreText;
+pub struct ScoreText(pub usize);
+
+#[derive(Component, Default)]
+pub struct Bird {
+    pub velocity: f32,
+}
 
+/// Advances the bird's wing flap frame every time it finishes
 #[derive(Component)]
-pub struct Bird;
+pub struct BirdAnimation(pub Timer);
+
+/// Parent of an `UpperPipe` and a `LowerPipe`, scrolling and recycling them together
+#[derive(Component, Default)]
+pub struct PipePair {
+    /// Whether the bird already scored for flying through this pair
+    pub passed: bool,
+}
 
 #[derive(Component)]
 pub struct UpperPipe;
 
 #[derive(Component)]
 pub struct LowerPipe;
+
+/// Flies the best earlier run with the same seed alongside the bird
+#[derive(Component, Default)]
+pub struct Ghost {
+    pub velocity: f32,
+    /// Index of the replay's next flap
+    pub next_flap: usize,
+    pub crashed: bool,
+}
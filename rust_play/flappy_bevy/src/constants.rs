@@ -1,2 +1,38 @@
+use bevy::math::Vec2;
+
+// Gameplay runs at this fixed rate regardless of the frame rate, so replays play out exactly
+pub const FIXED_TIMESTEP_HZ: f64 = 60.0;
+
+// Virtual resolution the scene is laid out in, scaled to fit whatever the window size is
 pub const WINDOW_WIDTH: f32 = 800.0;
 pub const WINDOW_HEIGHT: f32 = 512.0;
+
+pub const BIRD_SIZE: Vec2 = Vec2::new(34.0, 24.0);
+pub const BIRD_FRAMES: usize = 3;
+pub const BIRD_FRAME_SECONDS: f32 = 0.1;
+// Tilt in radians per unit of vertical velocity, limited to the range below
+pub const BIRD_TILT_PER_VELOCITY: f32 = 0.0015;
+pub const BIRD_MAX_TILT_UP: f32 = 0.45;
+pub const BIRD_MAX_TILT_DOWN: f32 = std::f32::consts::FRAC_PI_2;
+
+pub const PIPE_SIZE: Vec2 = Vec2::new(52.0, 320.0);
+pub const PIPE_COUNT: usize = 5;
+
+pub const GROUND_HEIGHT: f32 = 112.0;
+// How much of the ground shows above the bottom edge, the rest hangs below it
+pub const GROUND_VISIBLE_HEIGHT: f32 = 62.0;
+pub const GROUND_Y: f32 = -WINDOW_HEIGHT / 2. + GROUND_VISIBLE_HEIGHT - GROUND_HEIGHT / 2.;
+pub const GROUND_TILE_WIDTH: f32 = 336.0;
+pub const BACKGROUND_TILE_WIDTH: f32 = 288.0;
+
+// Center of the leftmost score digit, measured in from the top left corner of the scene
+pub const SCORE_MARGIN: Vec2 = Vec2::new(50.0, 56.0);
+
+pub const HIGH_SCORES_PATH: &str = "highscores.ron";
+pub const BINDINGS_PATH: &str = "bindings.ron";
+pub const REPLAYS_DIR: &str = "replays";
+pub const SETTINGS_PATH: &str = "settings.ron";
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const MAX_NAME_LENGTH: usize = 12;
+
+pub const BRAIN_PATH: &str = "best.brain.ron";
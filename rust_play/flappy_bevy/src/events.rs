@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+#[derive(Message)]
+pub struct Flapped;
+
+/// The bird flew through a pipe pair
+#[derive(Message)]
+pub struct Scored;
+
+/// The bird hit a pipe or the ground, ending the run
+#[derive(Message)]
+pub struct Crashed {
+    pub into_ground: bool,
+}
@@ -0,0 +1,167 @@
+use std::fs;
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{components::*, constants::*, score::Score};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub date: String,
+}
+
+/// Best scores, highest first, persisted to [`HIGH_SCORES_PATH`]
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    pub fn load(path: &str) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Ignoring unreadable high scores in {path}: {err}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(path, contents).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Failed to save high scores to {path}: {err}");
+        }
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_some_and(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        self.entries.push(entry);
+        // Stable, so older entries stay ahead of newer ones with the same score
+        self.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.entries.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+/// Name being typed in for a new high score. Restarting waits until it's submitted.
+#[derive(Resource, Debug)]
+pub struct NameEntry(pub String);
+
+/// Last entered name, offered again for the next high score
+#[derive(Resource, Debug)]
+pub struct PlayerName(pub String);
+
+impl Default for PlayerName {
+    fn default() -> Self {
+        Self("Player".to_string())
+    }
+}
+
+pub fn begin_name_entry(
+    mut commands: Commands,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    player_name: Res<PlayerName>,
+) {
+    if high_scores.qualifies(score.0) {
+        commands.insert_resource(NameEntry(player_name.0.clone()));
+    }
+}
+
+pub fn enter_name(
+    mut commands: Commands,
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut name_entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    mut player_name: ResMut<PlayerName>,
+    score: Res<Score>,
+) {
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| c.is_alphanumeric()) {
+                    if name_entry.0.chars().count() < MAX_NAME_LENGTH {
+                        name_entry.0.push(c);
+                    }
+                }
+            }
+            Key::Backspace => {
+                name_entry.0.pop();
+            }
+            Key::Enter => {
+                if !name_entry.0.is_empty() {
+                    player_name.0 = name_entry.0.clone();
+                }
+                high_scores.insert(HighScoreEntry {
+                    name: player_name.0.clone(),
+                    score: score.0,
+                    date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+                });
+                high_scores.save(HIGH_SCORES_PATH);
+                commands.remove_resource::<NameEntry>();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn show_high_scores(mut query: Query<&mut Visibility, With<HighScoreText>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Visible;
+    }
+}
+
+pub fn hide_high_scores(mut query: Query<&mut Visibility, With<HighScoreText>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn update_high_score_text(
+    high_scores: Res<HighScores>,
+    name_entry: Option<Res<NameEntry>>,
+    mut query: Query<&mut Text2d, With<HighScoreText>>,
+) {
+    let mut text = String::from("HIGH SCORES\n");
+    for (i, entry) in high_scores.entries.iter().enumerate() {
+        text += &format!(
+            "{:>2}. {:<12} {:>3}  {}\n",
+            i + 1,
+            entry.name,
+            entry.score,
+            entry.date
+        );
+    }
+    if let Some(name_entry) = name_entry {
+        text += &format!(
+            "\nNew high score! Name: {}_\nPress Enter to save",
+            name_entry.0
+        );
+    }
+
+    for mut high_score_text in query.iter_mut() {
+        if high_score_text.0 != text {
+            high_score_text.0.clone_from(&text);
+        }
+    }
+}
@@ -1,22 +1,183 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::ScheduleConfigs, ecs::system::ScheduleSystem, prelude::*};
+use clap::Parser;
 
+mod actions;
+mod args;
+mod audio;
+mod autopilot;
+mod brain;
+mod camera;
+mod collision;
 mod components;
 mod constants;
+mod events;
+mod highscore;
+mod menu;
 mod plugin;
+mod replay;
+mod rng;
+mod score;
+mod settings;
 mod setup;
+mod state;
 mod system;
+mod training;
+mod tuning;
 mod utils;
+mod weather;
 
+use crate::actions::*;
+use crate::args::Args;
+use crate::audio::*;
+use crate::autopilot::*;
+use crate::brain::Brain;
+use crate::camera::*;
+use crate::collision::*;
+use crate::events::*;
+use crate::highscore::*;
+use crate::menu::*;
 use crate::plugin::*;
+use crate::replay::*;
+use crate::rng::*;
+use crate::score::*;
+use crate::settings::*;
 use crate::setup::*;
+use crate::state::*;
 use crate::system::*;
+use crate::tuning::*;
+use crate::weather::*;
 
 fn main() {
-    App::new()
-        .add_plugins(MyPlugin)
-        .add_systems(Startup, (hello_world, setup))
-        .add_systems(Update, blink_space_bar_text)
-        .run();
+    let args = Args::parse();
+    if let Some(generations) = args.train {
+        training::train(&args, generations);
+        return;
+    }
+    let seed = args.fixed_seed().unwrap_or_else(rand::random);
+    let saved_settings = Settings::load(constants::SETTINGS_PATH);
+
+    let mut app = App::new();
+    if args.autopilot {
+        match Brain::load(constants::BRAIN_PATH) {
+            Ok(brain) => app.insert_resource(Autopilot(brain)),
+            Err(err) => {
+                eprintln!("{err}, train one with --train <GENERATIONS>");
+                return;
+            }
+        };
+    }
+
+    app.add_plugins((
+        MyPlugin,
+        ActionPlugin,
+        TuningPlugin,
+        SoundPlugin,
+        ViewportPlugin,
+        WeatherPlugin,
+        SettingsPlugin,
+    ))
+    .add_systems(Startup, (hello_world, setup))
+    .init_state::<GameState>()
+    .add_sub_state::<PlayState>()
+    .add_plugins(MenuPlugin)
+    .insert_resource(GameRng::new(seed))
+    .insert_resource(Time::<Fixed>::from_hz(constants::FIXED_TIMESTEP_HZ))
+    .insert_resource(saved_settings.clone().with_args(&args))
+    .insert_resource(SavedSettings(saved_settings))
+    .insert_resource(args)
+    .add_message::<Flapped>()
+    .add_message::<Scored>()
+    .add_message::<Crashed>()
+    .init_resource::<Score>()
+    .init_resource::<Run>()
+    .init_resource::<QueuedFlap>()
+    .init_resource::<Recording>()
+    .init_resource::<GhostReplay>()
+    .init_resource::<PlayerName>()
+    .insert_resource(HighScores::load(constants::HIGH_SCORES_PATH))
+    .add_systems(OnEnter(GameState::Playing), start_run())
+    // Restarting from the pause menu
+    .add_systems(
+        OnTransition {
+            exited: GameState::Playing,
+            entered: GameState::Playing,
+        },
+        start_run(),
+    )
+    .add_systems(OnEnter(PlayState::Paused), pause_time)
+    .add_systems(OnExit(PlayState::Paused), unpause_time)
+    .add_systems(
+        OnEnter(GameState::GameOver),
+        (
+            show_game_over_text,
+            show_space_bar_text,
+            show_high_scores,
+            save_replay,
+            // The AI's scores don't go into the high score table
+            begin_name_entry.run_if(not(resource_exists::<Autopilot>)),
+        ),
+    )
+    .add_systems(
+        OnExit(GameState::GameOver),
+        (hide_game_over_text, hide_high_scores),
+    )
+    .add_systems(
+        RunFixedMainLoop,
+        queue_flap
+            .in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop)
+            .run_if(in_state(PlayState::Running).and(not(resource_exists::<Autopilot>))),
+    )
+    // Everything a replay has to reproduce steps at the fixed rate
+    .add_systems(
+        FixedUpdate,
+        (
+            flap.run_if(not(resource_exists::<Autopilot>)),
+            autopilot_flap.run_if(resource_exists::<Autopilot>),
+            apply_gravity,
+            tilt_bird,
+            scroll_pipes,
+            (fly_ghost, crash_ghost).chain(),
+            count_score,
+            detect_collisions,
+            record_flaps,
+            advance_run,
+        )
+            .chain()
+            .run_if(in_state(PlayState::Running).and(run_in_progress)),
+    )
+    .add_systems(
+        Update,
+        (
+            (
+                blink_space_bar_text,
+                start_playing.run_if(not(resource_exists::<NameEntry>)),
+            )
+                .run_if(not(in_state(GameState::Playing))),
+            toggle_pause.run_if(in_state(GameState::Playing)),
+            (scroll_ground, scroll_background, animate_bird)
+                .run_if(not(in_state(GameState::GameOver))),
+            update_score_text,
+            (
+                enter_name.run_if(resource_exists::<NameEntry>),
+                update_high_score_text,
+            )
+                .chain()
+                .run_if(in_state(GameState::GameOver)),
+        ),
+    )
+    .run();
+}
+
+fn start_run() -> ScheduleConfigs<ScheduleSystem> {
+    (
+        reset_run,
+        reset_bird,
+        (reset_pipes, start_recording, reset_ghost).after(reseed_rng),
+        reseed_rng,
+        reset_score,
+        hide_space_bar_text,
+    )
+        .into_configs()
 }
 
 fn hello_world() {
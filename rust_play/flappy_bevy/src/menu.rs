@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    audio::VOLUME_STEP,
+    settings::Settings,
+    state::{GameState, PlayState},
+};
+
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON_COLOR: Color = Color::srgb(0.35, 0.55, 0.35);
+
+/// Which page of the menu is open while paused
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(PlayState = PlayState::Paused)]
+pub enum MenuScreen {
+    #[default]
+    Pause,
+    Settings,
+}
+
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    Resume,
+    Restart,
+    VolumeDown,
+    VolumeUp,
+    Mute,
+    Fullscreen,
+    Settings,
+    Back,
+}
+
+/// Text showing the current value of a setting
+#[derive(Component, Clone, Copy)]
+enum SettingText {
+    Volume,
+    Muted,
+    Fullscreen,
+}
+
+fn screen(screen: MenuScreen, title: &str) -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        DespawnOnExit(screen),
+        children![(Text::new(title), TextFont::from_font_size(32.))],
+    )
+}
+
+fn button(action: MenuButton, width: f32, label: impl Bundle) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(40.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        action,
+        children![(label, TextFont::from_font_size(20.))],
+    )
+}
+
+fn volume_row() -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.),
+            ..default()
+        },
+        children![
+            button(MenuButton::VolumeDown, 40., Text::new("-")),
+            (
+                Text::default(),
+                TextFont::from_font_size(20.),
+                SettingText::Volume
+            ),
+            button(MenuButton::VolumeUp, 40., Text::new("+")),
+        ],
+    )
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .spawn(screen(MenuScreen::Pause, "PAUSED"))
+        .with_children(|menu| {
+            menu.spawn(button(MenuButton::Resume, 200., Text::new("Resume")));
+            menu.spawn(button(MenuButton::Restart, 200., Text::new("Restart")));
+            menu.spawn(volume_row());
+            menu.spawn(button(
+                MenuButton::Fullscreen,
+                200.,
+                (Text::default(), SettingText::Fullscreen),
+            ));
+            menu.spawn(button(MenuButton::Settings, 200., Text::new("Settings")));
+        });
+}
+
+fn spawn_settings_menu(mut commands: Commands) {
+    commands
+        .spawn(screen(MenuScreen::Settings, "SETTINGS"))
+        .with_children(|menu| {
+            menu.spawn(volume_row());
+            menu.spawn(button(
+                MenuButton::Mute,
+                200.,
+                (Text::default(), SettingText::Muted),
+            ));
+            menu.spawn(button(
+                MenuButton::Fullscreen,
+                200.,
+                (Text::default(), SettingText::Fullscreen),
+            ));
+            menu.spawn(button(MenuButton::Back, 200., Text::new("Back")));
+        });
+}
+
+fn press_menu_buttons(
+    query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut settings: ResMut<Settings>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+    mut next_screen: ResMut<NextState<MenuScreen>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Resume => next_play_state.set(PlayState::Running),
+            MenuButton::Restart => {
+                // Entering `Playing` again starts a fresh run
+                next_game_state.set(GameState::Playing);
+                next_play_state.set(PlayState::Running);
+            }
+            MenuButton::VolumeDown => settings.volume = (settings.volume - VOLUME_STEP).max(0.),
+            MenuButton::VolumeUp => settings.volume = (settings.volume + VOLUME_STEP).min(1.),
+            MenuButton::Mute => settings.muted = !settings.muted,
+            MenuButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            MenuButton::Settings => next_screen.set(MenuScreen::Settings),
+            MenuButton::Back => next_screen.set(MenuScreen::Pause),
+        }
+    }
+}
+
+fn color_menu_buttons(
+    mut query: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut color) in query.iter_mut() {
+        color.0 = match interaction {
+            Interaction::Pressed => PRESSED_BUTTON_COLOR,
+            Interaction::Hovered => HOVERED_BUTTON_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+fn update_setting_texts(settings: Res<Settings>, mut query: Query<(&SettingText, &mut Text)>) {
+    for (setting, mut text) in query.iter_mut() {
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        let value = match setting {
+            SettingText::Volume => format!("Volume {:.0}%", settings.volume * 100.),
+            SettingText::Muted => format!("Sound: {}", on_off(!settings.muted)),
+            SettingText::Fullscreen => format!("Fullscreen: {}", on_off(settings.fullscreen)),
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<MenuScreen>()
+            .add_systems(OnEnter(MenuScreen::Pause), spawn_pause_menu)
+            .add_systems(OnEnter(MenuScreen::Settings), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (press_menu_buttons, color_menu_buttons, update_setting_texts)
+                    .run_if(in_state(PlayState::Paused)),
+            );
+    }
+}
@@ -9,7 +9,6 @@ impl Plugin for MyPlugin {
             primary_window: Some(Window {
                 title: "Flappy Bevy".to_string(),
                 resolution: (WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32).into(),
-                // resizable: false,
                 ..default()
             }),
             ..default()
@@ -0,0 +1,391 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{hits_ground, hits_pipe_pair, pipe_pair_position},
+    components::*,
+    constants::REPLAYS_DIR,
+    events::Flapped,
+    rng::GameRng,
+    score::Score,
+    state::Run,
+    system::{fall, tilt},
+    tuning::Tuning,
+};
+
+/// Everything needed to play a run again: the pipes follow from the seed, the bird from the flaps
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Replay {
+    pub seed: u64,
+    /// [`Tuning::fingerprint`] of the run. Replays without one come from before it was recorded
+    /// and never match.
+    #[serde(default)]
+    pub tuning: u64,
+    pub score: u32,
+    /// Fixed timesteps since the start of the run at which the bird flapped
+    pub flaps: Vec<u32>,
+}
+
+impl Replay {
+    /// Whether the run played out with `tuning`, as anything else makes the ghost diverge
+    pub fn matches(&self, tuning: &Tuning) -> bool {
+        self.tuning == tuning.fingerprint()
+    }
+
+    pub fn load(path: &str) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        ron::from_str(&contents)
+            .inspect_err(|err| warn!("Ignoring unreadable replay {path}: {err}"))
+            .ok()
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = fs::create_dir_all(REPLAYS_DIR)
+            .map_err(|err| err.to_string())
+            .and_then(|()| {
+                ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                    .map_err(|err| err.to_string())
+            })
+            .and_then(|contents| fs::write(path, contents).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Failed to save the replay to {path}: {err}");
+        }
+    }
+}
+
+fn last_replay_path() -> String {
+    format!("{REPLAYS_DIR}/last.replay.ron")
+}
+
+fn best_replay_path(seed: u64) -> String {
+    format!("{REPLAYS_DIR}/{seed}.replay.ron")
+}
+
+/// The run being played
+#[derive(Resource, Debug, Default)]
+pub struct Recording(pub Replay);
+
+/// The best run with the current seed, if there is one
+#[derive(Resource, Debug, Default)]
+pub struct GhostReplay(pub Option<Replay>);
+
+pub fn start_recording(
+    tuning: Res<Tuning>,
+    game_rng: Res<GameRng>,
+    mut recording: ResMut<Recording>,
+) {
+    recording.0 = Replay {
+        seed: game_rng.seed,
+        tuning: tuning.fingerprint(),
+        ..default()
+    };
+}
+
+pub fn record_flaps(
+    run: Res<Run>,
+    mut flapped: MessageReader<Flapped>,
+    mut recording: ResMut<Recording>,
+) {
+    for _ in flapped.read() {
+        recording.0.flaps.push(run.tick);
+    }
+}
+
+// Keeps the run as the last one, and as the seed's best if no earlier run with the same tuning
+// got further. Runs the tuning was reloaded during can't be played back, so they're dropped.
+pub fn save_replay(score: Res<Score>, tuning: Res<Tuning>, mut recording: ResMut<Recording>) {
+    if !recording.0.matches(&tuning) {
+        info!("Not saving the replay, the tuning changed during the run");
+        return;
+    }
+    recording.0.score = score.0;
+    recording.0.save(&last_replay_path());
+    let best_path = best_replay_path(recording.0.seed);
+    if Replay::load(&best_path).is_none_or(|best| !best.matches(&tuning) || score.0 > best.score) {
+        recording.0.save(&best_path);
+    }
+}
+
+pub fn reset_ghost(
+    tuning: Res<Tuning>,
+    game_rng: Res<GameRng>,
+    mut ghost_replay: ResMut<GhostReplay>,
+    mut query: Query<(&mut Ghost, &mut Transform, &mut Visibility)>,
+) {
+    ghost_replay.0 =
+        Replay::load(&best_replay_path(game_rng.seed)).filter(|replay| replay.matches(&tuning));
+    for (mut ghost, mut transform, mut visibility) in query.iter_mut() {
+        *ghost = Ghost {
+            velocity: tuning.flap_velocity,
+            next_flap: 0,
+            crashed: ghost_replay.0.is_none(),
+        };
+        transform.translation.y = 0.;
+        transform.rotation = Quat::IDENTITY;
+        *visibility = if ghost.crashed {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+    }
+}
+
+// Same steps as the bird takes, with the flaps coming from the replay instead of the player
+pub fn fly_ghost(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    run: Res<Run>,
+    ghost_replay: Res<GhostReplay>,
+    mut query: Query<(&mut Ghost, &mut Transform)>,
+) {
+    let Some(replay) = &ghost_replay.0 else {
+        return;
+    };
+    for (mut ghost, mut transform) in query.iter_mut() {
+        if ghost.crashed {
+            continue;
+        }
+        while replay.flaps.get(ghost.next_flap) == Some(&run.tick) {
+            ghost.velocity = tuning.flap_velocity;
+            ghost.next_flap += 1;
+        }
+        fall(
+            &tuning,
+            time.delta_secs(),
+            &mut transform.translation.y,
+            &mut ghost.velocity,
+        );
+        transform.rotation = tilt(ghost.velocity);
+    }
+}
+
+pub fn crash_ghost(
+    tuning: Res<Tuning>,
+    ghost_replay: Res<GhostReplay>,
+    mut ghosts: Query<(&mut Ghost, &Transform, &mut Visibility)>,
+    pairs: Query<(&Transform, &Children), With<PipePair>>,
+    pipes: Query<(&Transform, Has<UpperPipe>), Without<PipePair>>,
+) {
+    // Reloaded mid-run, so the ghost can't follow its replay anymore
+    let diverged = tuning.is_changed()
+        && ghost_replay
+            .0
+            .as_ref()
+            .is_some_and(|replay| !replay.matches(&tuning));
+    for (mut ghost, transform, mut visibility) in ghosts.iter_mut() {
+        if ghost.crashed {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let hit_pipe = pairs.iter().any(|(pair, children)| {
+            let (lower_y, upper_y) = pipe_pair_position(children, &pipes);
+            hits_pipe_pair(position, pair.translation.x, lower_y, upper_y)
+        });
+        if diverged || hit_pipe || hits_ground(position) {
+            ghost.crashed = true;
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+    use clap::Parser;
+
+    use super::*;
+    use crate::{
+        args::Args,
+        brain::next_pair,
+        collision::detect_collisions,
+        constants::{FIXED_TIMESTEP_HZ, PIPE_COUNT, PIPE_SIZE},
+        events::{Crashed, Scored},
+        rng::reseed_rng,
+        score::{count_score, reset_score},
+        state::*,
+        system::*,
+    };
+
+    // The gameplay half of the game, stepping once per update
+    fn app(seed: u64, ghost: Option<Replay>) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                1. / FIXED_TIMESTEP_HZ,
+            )))
+            .insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
+            .insert_resource(Args::parse_from([
+                "flappy_bevy",
+                "--seed",
+                &seed.to_string(),
+            ]))
+            .insert_resource(GameRng::new(seed))
+            .insert_resource(GhostReplay(ghost))
+            .init_resource::<Tuning>()
+            .init_resource::<Score>()
+            .init_resource::<Run>()
+            .init_resource::<QueuedFlap>()
+            .init_resource::<Recording>()
+            .add_message::<Flapped>()
+            .add_message::<Scored>()
+            .add_message::<Crashed>()
+            .insert_state(GameState::Playing)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    reset_run,
+                    reset_bird,
+                    (reset_pipes, start_recording).after(reseed_rng),
+                    reseed_rng,
+                    reset_score,
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    flap,
+                    apply_gravity,
+                    scroll_pipes,
+                    (fly_ghost, crash_ghost).chain(),
+                    count_score,
+                    detect_collisions,
+                    record_flaps,
+                    advance_run,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing).and(run_in_progress)),
+            );
+
+        let world = app.world_mut();
+        world.spawn((Transform::default(), Bird::default()));
+        world.spawn((
+            Transform::default(),
+            Visibility::Hidden,
+            Ghost {
+                velocity: Tuning::default().flap_velocity,
+                ..default()
+            },
+        ));
+        for _ in 0..PIPE_COUNT {
+            world.spawn((
+                Transform::default(),
+                PipePair::default(),
+                children![
+                    (Transform::default(), LowerPipe),
+                    (Transform::default(), UpperPipe)
+                ],
+            ));
+        }
+        // The first update only starts the clocks, and enters the state
+        app.update();
+        app
+    }
+
+    fn height<T: Component>(world: &mut World) -> f32 {
+        let mut query = world.query_filtered::<&Transform, With<T>>();
+        query.single(world).unwrap().translation.y
+    }
+
+    // Top of the lower pipe the bird has to clear next
+    fn next_gap_bottom(world: &mut World) -> f32 {
+        let mut pairs = world.query_filtered::<(&Transform, &Children), With<PipePair>>();
+        let pairs: Vec<_> = pairs
+            .iter(world)
+            .map(|(pair, children)| {
+                let lower_y = children
+                    .iter()
+                    .filter(|&child| world.get::<LowerPipe>(child).is_some())
+                    .map(|child| world.get::<Transform>(child).unwrap().translation.y)
+                    .sum::<f32>();
+                (pair.translation.x, (lower_y, 0.))
+            })
+            .collect();
+        let (_, (lower_y, _)) = next_pair(0., pairs).unwrap();
+        lower_y + PIPE_SIZE.y / 2.
+    }
+
+    /// Plays until the crash, returning the bird's and the ghost's heights at every step
+    fn play(app: &mut App, mut flap_at: impl FnMut(&mut World, u32) -> bool) -> Vec<(f32, f32)> {
+        let mut heights = Vec::new();
+        while !app.world().resource::<Run>().over {
+            let tick = app.world().resource::<Run>().tick;
+            assert!(tick < 100_000, "the bird never crashed");
+            if flap_at(app.world_mut(), tick) {
+                app.world_mut().resource_mut::<QueuedFlap>().0 = true;
+            }
+            app.update();
+            let world = app.world_mut();
+            heights.push((height::<Bird>(world), height::<Ghost>(world)));
+        }
+        heights
+    }
+
+    #[test]
+    fn ghost_replays_the_run_exactly() {
+        // Aims for the gaps, getting through a few before crashing
+        let mut recorded = app(7, None);
+        let recorded_heights = play(&mut recorded, |world, _| {
+            height::<Bird>(world) < next_gap_bottom(world) + 30.
+        });
+        let replay = recorded.world().resource::<Recording>().0.clone();
+        assert!(recorded.world().resource::<Score>().0 > 0);
+
+        // The player flaps at the same ticks, except for the first flap that comes with starting
+        // the run, so the bird and the ghost should fly as one
+        let mut replayed = app(7, Some(replay.clone()));
+        let replayed_heights = play(&mut replayed, |_, tick| replay.flaps[1..].contains(&tick));
+
+        assert_eq!(
+            replayed.world().resource::<Recording>().0.flaps,
+            replay.flaps
+        );
+        assert_eq!(
+            recorded_heights
+                .iter()
+                .map(|(bird, _)| bird)
+                .collect::<Vec<_>>(),
+            replayed_heights
+                .iter()
+                .map(|(bird, _)| bird)
+                .collect::<Vec<_>>()
+        );
+        for (bird, ghost) in replayed_heights {
+            assert_eq!(bird, ghost);
+        }
+        let world = replayed.world_mut();
+        assert!(world.query::<&Ghost>().single(world).unwrap().crashed);
+    }
+
+    #[test]
+    fn ghost_drops_out_when_the_tuning_changes() {
+        let mut recorded = app(7, None);
+        play(&mut recorded, |world, _| {
+            height::<Bird>(world) < next_gap_bottom(world) + 30.
+        });
+        let replay = recorded.world().resource::<Recording>().0.clone();
+        assert!(replay.matches(&Tuning::default()));
+
+        let mut replayed = app(7, Some(replay));
+        replayed.update();
+        let world = replayed.world_mut();
+        assert!(!world.query::<&Ghost>().single(world).unwrap().crashed);
+
+        // Only the gameplay numbers count, the weather is just for looks
+        world.resource_mut::<Tuning>().weather.rain = true;
+        replayed.update();
+        let world = replayed.world_mut();
+        assert!(!world.query::<&Ghost>().single(world).unwrap().crashed);
+
+        world.resource_mut::<Tuning>().gravity *= 2.;
+        replayed.update();
+        let world = replayed.world_mut();
+        let mut ghosts = world.query::<(&Ghost, &Visibility)>();
+        let (ghost, visibility) = ghosts.single(world).unwrap();
+        assert!(ghost.crashed);
+        assert_eq!(visibility, Visibility::Hidden);
+    }
+}
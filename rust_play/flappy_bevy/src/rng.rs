@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::args::Args;
+
+/// The only source of randomness in the game, so a seed and the player's inputs fully
+/// determine a run. ChaCha8 rather than `StdRng`, whose algorithm may change between releases.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: ChaCha8Rng,
+    /// A separate stream of the same seed for decoration like the weather, so drawing from it
+    /// can't change which pipes a seed produces
+    pub decoration: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        let mut decoration = ChaCha8Rng::seed_from_u64(seed);
+        decoration.set_stream(1);
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            decoration,
+        }
+    }
+}
+
+// Every run starts from a fresh seed, unless one was given on the command line
+pub fn reseed_rng(args: Res<Args>, mut game_rng: ResMut<GameRng>) {
+    *game_rng = GameRng::new(args.fixed_seed().unwrap_or_else(rand::random));
+    info!("Run seed: {}", game_rng.seed);
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::{components::*, events::Scored};
+
+#[derive(Resource, Debug, Default)]
+pub struct Score(pub u32);
+
+pub fn reset_score(mut score: ResMut<Score>) {
+    score.0 = 0;
+}
+
+// A pair counts as passed once its center is behind the bird
+pub fn count_score(
+    mut score: ResMut<Score>,
+    mut scored: MessageWriter<Scored>,
+    birds: Query<&Transform, With<Bird>>,
+    mut pairs: Query<(&mut PipePair, &Transform)>,
+) {
+    for bird in birds.iter() {
+        for (mut pair, transform) in pairs.iter_mut() {
+            if !pair.passed && transform.translation.x < bird.translation.x {
+                pair.passed = true;
+                score.0 += 1;
+                scored.write(Scored);
+            }
+        }
+    }
+}
+
+/// Atlas indices of the three score digits, `None` for hidden leading zeros
+pub fn score_digits(score: u32) -> [Option<usize>; 3] {
+    let score = score.min(999);
+    let digits = [score / 100, score / 10 % 10, score % 10];
+    let first_shown = if score >= 100 {
+        0
+    } else if score >= 10 {
+        1
+    } else {
+        2
+    };
+    std::array::from_fn(|i| (i >= first_shown).then_some(digits[i] as usize))
+}
+
+pub fn update_score_text(
+    score: Res<Score>,
+    mut query: Query<(&ScoreText, &mut Sprite, &mut Visibility)>,
+) {
+    if !score.is_changed() {
+        return;
+    }
+    let digits = score_digits(score.0);
+    for (text, mut sprite, mut visibility) in query.iter_mut() {
+        match digits[text.0] {
+            Some(digit) => {
+                if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                    atlas.index = digit;
+                }
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
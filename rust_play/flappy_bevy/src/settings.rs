@@ -0,0 +1,112 @@
+use std::fs;
+
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{args::Args, constants::SETTINGS_PATH};
+
+/// Player preferences, persisted to [`SETTINGS_PATH`] whenever they change
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// Linear, from 0 to 1
+    pub volume: f32,
+    pub muted: bool,
+    pub fullscreen: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            fullscreen: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(path: &str) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Ignoring unreadable settings in {path}: {err}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(path, contents).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Failed to save settings to {path}: {err}");
+        }
+    }
+
+    /// Command line flags take precedence over the saved settings, for this launch only
+    pub fn with_args(mut self, args: &Args) -> Self {
+        self.muted |= args.mute;
+        if let Some(volume) = args.volume {
+            self.volume = volume;
+        }
+        self.volume = self.volume.clamp(0., 1.);
+        self
+    }
+}
+
+/// The settings as on disk, without the command line overrides that [`Settings`] has
+#[derive(Resource, Debug)]
+pub struct SavedSettings(pub Settings);
+
+// Saves only what the player changed, so a one-off `--mute` doesn't mute every later launch
+fn save_settings(
+    settings: Res<Settings>,
+    mut saved: ResMut<SavedSettings>,
+    mut previous: Local<Option<Settings>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Some(previous) = previous.replace(settings.clone()) else {
+        return;
+    };
+    if settings.volume != previous.volume {
+        saved.0.volume = settings.volume;
+    }
+    if settings.muted != previous.muted {
+        saved.0.muted = settings.muted;
+    }
+    if settings.fullscreen != previous.fullscreen {
+        saved.0.fullscreen = settings.fullscreen;
+    }
+    saved.0.save(SETTINGS_PATH);
+}
+
+fn apply_fullscreen(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    for mut window in windows.iter_mut() {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (save_settings, apply_fullscreen));
+    }
+}
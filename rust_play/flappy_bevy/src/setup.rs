@@ -1,22 +1,24 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::*,
-    constants::{WINDOW_HEIGHT, WINDOW_WIDTH},
-    utils::random_pipe_position,
+    components::*, constants::*, rng::GameRng, tuning::Tuning, utils::random_pipe_position,
 };
 
 pub fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    tuning: Res<Tuning>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    commands.spawn(Camera2d);
-
     commands.spawn((
         Sprite {
             image: asset_server.load("texture/background.png"),
-            custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+            // One extra tile on each side, so scrolling never uncovers an edge
+            custom_size: Some(Vec2::new(
+                WINDOW_WIDTH + 2. * BACKGROUND_TILE_WIDTH,
+                WINDOW_HEIGHT,
+            )),
             image_mode: SpriteImageMode::Tiled {
                 tile_x: true,
                 tile_y: true,
@@ -31,7 +33,10 @@ pub fn setup(
     commands.spawn((
         Sprite {
             image: asset_server.load("texture/base.png"),
-            custom_size: Some(Vec2::new(WINDOW_WIDTH, 112.)),
+            custom_size: Some(Vec2::new(
+                WINDOW_WIDTH + 2. * GROUND_TILE_WIDTH,
+                GROUND_HEIGHT,
+            )),
             image_mode: SpriteImageMode::Tiled {
                 tile_x: true,
                 tile_y: false,
@@ -39,7 +44,7 @@ pub fn setup(
             },
             ..default()
         },
-        Transform::from_xyz(0., -250.0, 1.),
+        Transform::from_xyz(0., GROUND_Y, 1.),
         Ground,
     ));
 
@@ -53,6 +58,15 @@ pub fn setup(
         GameOverText,
     ));
 
+    commands.spawn((
+        Text2d::default(),
+        TextFont::from_font_size(14.),
+        TextLayout::new_with_justify(Justify::Left),
+        Visibility::Hidden,
+        Transform::from_xyz(0., -140., 3.),
+        HighScoreText,
+    ));
+
     commands.spawn((
         Sprite {
             image: asset_server.load("texture/space.png"),
@@ -68,7 +82,7 @@ pub fn setup(
 
     for i in 0..3 {
         // 24.0 px is the width of a single digit, 2.0 px is the space between digits
-        let staring_point = -350.0 + (i as f32 * (24.0 + 2.));
+        let staring_point = -WINDOW_WIDTH / 2. + SCORE_MARGIN.x + (i as f32 * (24.0 + 2.));
         commands.spawn((
             Sprite::from_atlas_image(
                 asset_server.load("texture/numbers.png"),
@@ -77,12 +91,13 @@ pub fn setup(
                     index: 0,
                 },
             ),
-            Transform::from_xyz(staring_point, 200., 1.),
-            ScoreText,
+            Transform::from_xyz(staring_point, WINDOW_HEIGHT / 2. - SCORE_MARGIN.y, 1.),
+            ScoreText(i),
         ));
     }
 
-    let bird_layout = TextureAtlasLayout::from_grid(UVec2::new(34, 24), 3, 1, None, None);
+    let bird_layout =
+        TextureAtlasLayout::from_grid(UVec2::new(34, 24), BIRD_FRAMES as u32, 1, None, None);
     let bird_texture_atlas_layout: Handle<TextureAtlasLayout> =
         texture_atlas_layouts.add(bird_layout);
 
@@ -95,49 +110,62 @@ pub fn setup(
             },
         ),
         Transform::from_xyz(0., 0., 2.),
-        ScoreText,
+        Bird::default(),
+        BirdAnimation(Timer::from_seconds(
+            BIRD_FRAME_SECONDS,
+            TimerMode::Repeating,
+        )),
     ));
 
-    for i in 0..5 {
-        let delta_x = i as f32 * 200.; // space between pairs of pipes
-        let (lower_y, upper_y) = random_pipe_position();
-        let mut transform = Transform::from_xyz(200.0 + delta_x, lower_y, 0.5);
-
-        commands.spawn((
-            Sprite {
-                image: asset_server.load("texture/pipe.png"),
-                ..default()
-            },
-            transform,
-            LowerPipe,
-        ));
-
-        transform.rotate(Quat::from_rotation_z(std::f32::consts::PI));
-        transform.translation.y = upper_y;
-
-        commands.spawn((
-            Sprite {
-                image: asset_server.load("texture/pipe.png"),
-                ..default()
-            },
-            transform,
-            UpperPipe,
-        ));
-    }
-
-    let mut cloud_transform = Transform::from_xyz(0., 0., 2.);
-    cloud_transform.rotate(Quat::from_rotation_z(std::f32::consts::PI));
-
     commands.spawn((
         Sprite {
-            image: asset_server.load("texture/cloud.jpg"),
-            custom_size: Some(Vec2::new(WINDOW_WIDTH / 8.0, WINDOW_HEIGHT / 8.0)),
-            image_mode: SpriteImageMode::Scale(ScalingMode::FillCenter),
-            ..default()
+            color: Color::srgba(1., 1., 1., 0.5),
+            ..Sprite::from_atlas_image(
+                asset_server.load("texture/bird.png"),
+                TextureAtlas {
+                    layout: bird_texture_atlas_layout.clone(),
+                    index: 0,
+                },
+            )
         },
+        // Just behind the bird
+        Transform::from_xyz(0., 0., 1.9),
         Visibility::Hidden,
-        // Transform::from_xyz(0., 0., 2.),
-        cloud_transform,
-        CloudBlue,
+        Ghost::default(),
+        BirdAnimation(Timer::from_seconds(
+            BIRD_FRAME_SECONDS,
+            TimerMode::Repeating,
+        )),
     ));
+
+    for i in 0..PIPE_COUNT {
+        let delta_x = i as f32 * tuning.pipe_spacing;
+        let (lower_y, upper_y) = random_pipe_position(&mut game_rng.rng, &tuning, tuning.gap);
+        let mut upper_transform = Transform::from_xyz(0., upper_y, 0.);
+        upper_transform.rotate(Quat::from_rotation_z(std::f32::consts::PI));
+
+        commands.spawn((
+            Transform::from_xyz(200.0 + delta_x, 0., 0.5),
+            Visibility::default(),
+            PipePair::default(),
+            children![
+                (
+                    Sprite {
+                        image: asset_server.load("texture/pipe.png"),
+                        ..default()
+                    },
+                    Transform::from_xyz(0., lower_y, 0.),
+                    LowerPipe,
+                ),
+                (
+                    Sprite {
+                        image: asset_server.load("texture/pipe.png"),
+                        ..default()
+                    },
+                    upper_transform,
+                    UpperPipe,
+                ),
+            ],
+        ));
+    }
 }
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::Action,
+    components::*,
+    events::Flapped,
+    rng::GameRng,
+    system::{QueuedFlap, set_pipe_position},
+    tuning::Tuning,
+    utils::random_pipe_position,
+};
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Start,
+    Playing,
+    GameOver,
+}
+
+/// Whether a run is under way or paused, only exists while playing
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PlayState {
+    #[default]
+    Running,
+    Paused,
+}
+
+pub fn toggle_pause(
+    actions: Res<ButtonInput<Action>>,
+    play_state: Res<State<PlayState>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(match play_state.get() {
+            PlayState::Running => PlayState::Paused,
+            PlayState::Paused => PlayState::Running,
+        });
+    }
+}
+
+// Stops everything that moves with time, not only the gameplay systems that check the state
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// The current run, counted in fixed timesteps
+#[derive(Resource, Debug, Default)]
+pub struct Run {
+    pub tick: u32,
+    /// The bird crashed, though the state only changes once the current frame is over
+    pub over: bool,
+}
+
+pub fn reset_run(mut run: ResMut<Run>) {
+    *run = Run::default();
+}
+
+pub fn advance_run(mut run: ResMut<Run>) {
+    run.tick += 1;
+}
+
+pub fn run_in_progress(run: Res<Run>) -> bool {
+    !run.over
+}
+
+// Flapping starts the first game, and restarts after a game over
+pub fn start_playing(
+    actions: Res<ButtonInput<Action>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Flap) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+pub fn reset_bird(
+    tuning: Res<Tuning>,
+    mut queued_flap: ResMut<QueuedFlap>,
+    mut flapped: MessageWriter<Flapped>,
+    mut query: Query<(&mut Bird, &mut Transform)>,
+) {
+    queued_flap.0 = false;
+    for (mut bird, mut transform) in query.iter_mut() {
+        transform.translation.y = 0.;
+        transform.rotation = Quat::IDENTITY;
+        // The flap that started the game counts as the first one
+        bird.velocity = tuning.flap_velocity;
+        flapped.write(Flapped);
+    }
+}
+
+pub fn reset_pipes(
+    tuning: Res<Tuning>,
+    mut game_rng: ResMut<GameRng>,
+    mut pairs: Query<(&mut PipePair, &mut Transform, &Children)>,
+    mut pipes: Query<(&mut Transform, Has<UpperPipe>), Without<PipePair>>,
+) {
+    for (i, (mut pair, mut transform, children)) in pairs.iter_mut().enumerate() {
+        transform.translation.x = 200.0 + i as f32 * tuning.pipe_spacing;
+        pair.passed = false;
+        let position = random_pipe_position(&mut game_rng.rng, &tuning, tuning.gap_at(0));
+        set_pipe_position(children, &mut pipes, position);
+    }
+}
+
+pub fn show_game_over_text(mut query: Query<&mut Visibility, With<GameOverText>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Visible;
+    }
+}
+
+pub fn hide_game_over_text(mut query: Query<&mut Visibility, With<GameOverText>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn hide_space_bar_text(mut query: Query<&mut Visibility, With<PressSpaceBarText>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn show_space_bar_text(mut query: Query<(&mut PressSpaceBarText, &mut Visibility)>) {
+    for (mut text, mut visibility) in query.iter_mut() {
+        text.0.reset();
+        *visibility = Visibility::Visible;
+    }
+}
@@ -1,5 +1,169 @@
 use bevy::prelude::*;
 
-pub fn blink_space_bar_text(time: Res<Time>) {
-    dbg!(time.delta());
+use crate::{
+    actions::Action, components::*, constants::*, events::Flapped, rng::GameRng, score::Score,
+    tuning::Tuning, utils::random_pipe_position,
+};
+
+pub fn blink_space_bar_text(
+    time: Res<Time>,
+    mut query: Query<(&mut PressSpaceBarText, &mut Visibility)>,
+) {
+    for (mut text, mut visibility) in query.iter_mut() {
+        if text.0.tick(time.delta()).just_finished() {
+            visibility.toggle_visible_hidden();
+        }
+    }
+}
+
+/// A flap pressed since the last fixed timestep, which is where the bird flies
+#[derive(Resource, Default)]
+pub struct QueuedFlap(pub bool);
+
+pub fn queue_flap(actions: Res<ButtonInput<Action>>, mut queued: ResMut<QueuedFlap>) {
+    if actions.just_pressed(Action::Flap) {
+        queued.0 = true;
+    }
+}
+
+pub fn flap(
+    mut queued: ResMut<QueuedFlap>,
+    tuning: Res<Tuning>,
+    mut flapped: MessageWriter<Flapped>,
+    mut query: Query<&mut Bird>,
+) {
+    if std::mem::take(&mut queued.0) {
+        for mut bird in query.iter_mut() {
+            bird.velocity = tuning.flap_velocity;
+            flapped.write(Flapped);
+        }
+    }
+}
+
+/// One step of falling, shared by the bird, its ghost and the headless trainer
+pub fn fall(tuning: &Tuning, seconds: f32, y: &mut f32, velocity: &mut f32) {
+    *velocity += tuning.gravity * seconds;
+    *y += *velocity * seconds;
+
+    // Rest on the ground and don't fly off the top of the screen
+    let ground_top = GROUND_Y + GROUND_HEIGHT / 2.;
+    if *y < ground_top {
+        *y = ground_top;
+        *velocity = 0.;
+    }
+    *y = y.min(WINDOW_HEIGHT / 2.);
+}
+
+pub fn apply_gravity(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut query: Query<(&mut Bird, &mut Transform)>,
+) {
+    for (mut bird, mut transform) in query.iter_mut() {
+        fall(
+            &tuning,
+            time.delta_secs(),
+            &mut transform.translation.y,
+            &mut bird.velocity,
+        );
+    }
+}
+
+pub fn animate_bird(time: Res<Time>, mut query: Query<(&mut BirdAnimation, &mut Sprite)>) {
+    for (mut animation, mut sprite) in query.iter_mut() {
+        if animation.0.tick(time.delta()).just_finished()
+            && let Some(atlas) = sprite.texture_atlas.as_mut()
+        {
+            atlas.index = (atlas.index + 1) % BIRD_FRAMES;
+        }
+    }
+}
+
+/// Nose up right after a flap, turning nose down as the bird dives
+pub fn tilt(velocity: f32) -> Quat {
+    Quat::from_rotation_z(
+        (velocity * BIRD_TILT_PER_VELOCITY).clamp(-BIRD_MAX_TILT_DOWN, BIRD_MAX_TILT_UP),
+    )
+}
+
+pub fn tilt_bird(mut query: Query<(&Bird, &mut Transform)>) {
+    for (bird, mut transform) in query.iter_mut() {
+        transform.rotation = tilt(bird.velocity);
+    }
+}
+
+pub fn scroll_pipes(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    score: Res<Score>,
+    mut game_rng: ResMut<GameRng>,
+    mut pairs: Query<(&mut PipePair, &mut Transform, &Children)>,
+    mut pipes: Query<(&mut Transform, Has<UpperPipe>), Without<PipePair>>,
+) {
+    let distance = tuning.scroll_speed_at(score.0) * time.delta_secs();
+    for (_, mut transform, _) in pairs.iter_mut() {
+        transform.translation.x -= distance;
+    }
+
+    // Once off screen on the left, move behind the last pair with a new gap
+    let mut last_x = pairs
+        .iter()
+        .map(|(_, transform, _)| transform.translation.x)
+        .fold(f32::MIN, f32::max);
+    for (mut pair, mut transform, children) in pairs.iter_mut() {
+        if transform.translation.x < -WINDOW_WIDTH / 2. - PIPE_SIZE.x / 2. {
+            last_x += tuning.pipe_spacing;
+            transform.translation.x = last_x;
+            pair.passed = false;
+            let position = random_pipe_position(&mut game_rng.rng, &tuning, tuning.gap_at(score.0));
+            set_pipe_position(children, &mut pipes, position);
+        }
+    }
+}
+
+pub fn set_pipe_position(
+    children: &Children,
+    pipes: &mut Query<(&mut Transform, Has<UpperPipe>), Without<PipePair>>,
+    (lower_y, upper_y): (f32, f32),
+) {
+    for &child in children {
+        if let Ok((mut pipe, is_upper)) = pipes.get_mut(child) {
+            pipe.translation.y = if is_upper { upper_y } else { lower_y };
+        }
+    }
+}
+
+fn scroll_tiled(transform: &mut Transform, distance: f32, tile_width: f32) {
+    // Moving by a whole tile looks the same, so wrap around to keep the sprite on screen
+    transform.translation.x = (transform.translation.x - distance) % tile_width;
+}
+
+pub fn scroll_ground(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    score: Res<Score>,
+    mut query: Query<&mut Transform, With<Ground>>,
+) {
+    for mut transform in query.iter_mut() {
+        scroll_tiled(
+            &mut transform,
+            tuning.scroll_speed_at(score.0) * time.delta_secs(),
+            GROUND_TILE_WIDTH,
+        );
+    }
+}
+
+pub fn scroll_background(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    score: Res<Score>,
+    mut query: Query<&mut Transform, With<Background>>,
+) {
+    for mut transform in query.iter_mut() {
+        scroll_tiled(
+            &mut transform,
+            tuning.background_scroll_speed_at(score.0) * time.delta_secs(),
+            BACKGROUND_TILE_WIDTH,
+        );
+    }
 }
@@ -0,0 +1,270 @@
+use std::fs;
+
+use bevy::math::Vec2;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    args::Args,
+    brain::{Brain, next_pair, senses},
+    collision::{hits_ground, hits_pipe_pair},
+    constants::*,
+    system::fall,
+    tuning::{TUNING_PATH, Tuning},
+    utils::random_pipe_position,
+};
+
+// The game's fixed timestep, so brains learn exactly the physics they'll play with
+const TICK_SECONDS: f32 = 1. / FIXED_TIMESTEP_HZ as f32;
+// Five minutes without crashing, which counts as having mastered the game
+const MAX_TICKS: u32 = 5 * 60 * 60;
+const POPULATION: usize = 200;
+// The best brains of a generation survive into the next one unchanged
+const ELITES: usize = 10;
+const TOURNAMENT_SIZE: usize = 5;
+const MUTATION_RATE: f64 = 0.1;
+const MUTATION_STRENGTH: f32 = 0.5;
+// Courses kept out of training, so the best brains of different generations compare fairly
+const HELD_OUT_COURSES: usize = 5;
+
+struct SimBird {
+    y: f32,
+    velocity: f32,
+    alive: bool,
+    /// How long the bird survived, which is what evolution maximizes
+    ticks: u32,
+    score: u32,
+}
+
+struct SimPipePair {
+    x: f32,
+    position: (f32, f32),
+    passed: bool,
+}
+
+/// The game without rendering, flying a whole generation of birds through the same pipes.
+/// Mirrors `flap`, `apply_gravity`, `scroll_pipes`, `count_score` and `detect_collisions`.
+struct Simulation {
+    tuning: Tuning,
+    rng: ChaCha8Rng,
+    birds: Vec<SimBird>,
+    pairs: Vec<SimPipePair>,
+    /// Every bird flies at the same x, so the ones still alive share a score
+    score: u32,
+}
+
+impl Simulation {
+    fn new(tuning: Tuning, seed: u64, birds: usize) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let pairs = (0..PIPE_COUNT)
+            .map(|i| SimPipePair {
+                x: 200.0 + i as f32 * tuning.pipe_spacing,
+                position: random_pipe_position(&mut rng, &tuning, tuning.gap_at(0)),
+                passed: false,
+            })
+            .collect();
+        let birds = (0..birds)
+            .map(|_| SimBird {
+                y: 0.,
+                velocity: tuning.flap_velocity,
+                alive: true,
+                ticks: 0,
+                score: 0,
+            })
+            .collect();
+        Self {
+            tuning,
+            rng,
+            birds,
+            pairs,
+            score: 0,
+        }
+    }
+
+    fn step(&mut self, brains: &[Brain]) {
+        let tuning = &self.tuning;
+        let next = next_pair(0., self.pairs.iter().map(|pair| (pair.x, pair.position)));
+        for (bird, brain) in self.birds.iter_mut().zip(brains) {
+            if !bird.alive {
+                continue;
+            }
+            if let Some((pair_x, position)) = next
+                && brain.think(&senses(
+                    tuning,
+                    Vec2::new(0., bird.y),
+                    bird.velocity,
+                    pair_x,
+                    position,
+                ))
+            {
+                bird.velocity = tuning.flap_velocity;
+            }
+            fall(tuning, TICK_SECONDS, &mut bird.y, &mut bird.velocity);
+        }
+
+        let distance = tuning.scroll_speed_at(self.score) * TICK_SECONDS;
+        let mut last_x = self
+            .pairs
+            .iter()
+            .map(|pair| pair.x)
+            .fold(f32::MIN, f32::max);
+        for pair in &mut self.pairs {
+            pair.x -= distance;
+            if pair.x < -WINDOW_WIDTH / 2. - PIPE_SIZE.x / 2. {
+                last_x += tuning.pipe_spacing;
+                pair.x = last_x;
+                pair.passed = false;
+                pair.position =
+                    random_pipe_position(&mut self.rng, tuning, tuning.gap_at(self.score));
+            }
+            if !pair.passed && pair.x < 0. {
+                pair.passed = true;
+                self.score += 1;
+            }
+        }
+
+        for bird in self.birds.iter_mut().filter(|bird| bird.alive) {
+            let position = Vec2::new(0., bird.y);
+            bird.alive = !hits_ground(position)
+                && !self
+                    .pairs
+                    .iter()
+                    .any(|pair| hits_pipe_pair(position, pair.x, pair.position.0, pair.position.1));
+            bird.ticks += 1;
+            bird.score = self.score;
+        }
+    }
+
+    /// Plays until every bird crashed, or the survivors hit [`MAX_TICKS`]
+    fn run(&mut self, brains: &[Brain]) {
+        for _ in 0..MAX_TICKS {
+            if !self.birds.iter().any(|bird| bird.alive) {
+                break;
+            }
+            self.step(brains);
+        }
+    }
+}
+
+// Headless, so it reads the file itself instead of going through the asset server
+fn load_tuning() -> Tuning {
+    let path = format!("assets/{TUNING_PATH}");
+    fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| ron::from_str(&contents).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("Training with the default tuning, couldn't read {path}: {err}");
+            Tuning::default()
+        })
+}
+
+/// Tournament selection: the fittest of a few random brains, `ranked` being fittest first
+fn select<'a>(ranked: &'a [Brain], rng: &mut impl Rng) -> &'a Brain {
+    let winner = (0..TOURNAMENT_SIZE)
+        .map(|_| rng.gen_range(0..ranked.len()))
+        .min()
+        .unwrap_or(0);
+    &ranked[winner]
+}
+
+/// Flies a generation through one course, fittest first
+fn evaluate(tuning: &Tuning, seed: u64, population: Vec<Brain>) -> Vec<(Brain, SimBird)> {
+    let mut simulation = Simulation::new(tuning.clone(), seed, population.len());
+    simulation.run(&population);
+    let mut ranked: Vec<(Brain, SimBird)> = population.into_iter().zip(simulation.birds).collect();
+    ranked.sort_by_key(|(_, bird)| std::cmp::Reverse(bird.ticks));
+    ranked
+}
+
+/// Total ticks a brain survives over the held-out courses
+fn held_out_ticks(tuning: &Tuning, seeds: &[u64], brain: &Brain) -> u32 {
+    seeds
+        .iter()
+        .map(|&seed| {
+            let mut simulation = Simulation::new(tuning.clone(), seed, 1);
+            simulation.run(std::slice::from_ref(brain));
+            simulation.birds[0].ticks
+        })
+        .sum()
+}
+
+fn next_generation(ranked: &[Brain], rng: &mut impl Rng) -> Vec<Brain> {
+    let mut population: Vec<Brain> = ranked.iter().take(ELITES).cloned().collect();
+    while population.len() < POPULATION {
+        let mut child = select(ranked, rng).crossover(select(ranked, rng), rng);
+        child.mutate(rng, MUTATION_RATE, MUTATION_STRENGTH);
+        population.push(child);
+    }
+    population
+}
+
+/// Evolves brains for `generations`, saving the best one so far to [`BRAIN_PATH`]
+pub fn train(args: &Args, generations: u32) {
+    let tuning = load_tuning();
+    let seed = args.fixed_seed().unwrap_or_else(rand::random);
+    println!("Training seed: {seed}");
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut population: Vec<Brain> = (0..POPULATION).map(|_| Brain::random(&mut rng)).collect();
+    let held_out: Vec<u64> = (0..HELD_OUT_COURSES).map(|_| rng.next_u64()).collect();
+    let mut best_ticks = 0;
+
+    for generation in 1..=generations {
+        // Fresh pipes every generation, so brains can't just memorize one course
+        let ranked = evaluate(&tuning, rng.next_u64(), population);
+        let (best, best_bird) = &ranked[0];
+        let mean_score =
+            ranked.iter().map(|(_, bird)| bird.score).sum::<u32>() as f32 / ranked.len() as f32;
+        println!(
+            "Generation {generation}: best score {}, mean score {mean_score:.1}",
+            best_bird.score
+        );
+        // ties go to the later brain, which has had more training
+        let ticks = held_out_ticks(&tuning, &held_out, best);
+        if ticks >= best_ticks {
+            best_ticks = ticks;
+            if let Err(err) = best.save(BRAIN_PATH) {
+                eprintln!("{err}");
+            }
+        }
+
+        let ranked: Vec<Brain> = ranked.into_iter().map(|(brain, _)| brain).collect();
+        population = next_generation(&ranked, &mut rng);
+    }
+    println!("Saved the best brain to {BRAIN_PATH}, watch it play with --autopilot");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(seed: u64, brains: Vec<Brain>) -> Vec<u32> {
+        evaluate(&Tuning::default(), seed, brains)
+            .into_iter()
+            .map(|(_, bird)| bird.ticks)
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_plays_out_the_same() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let brains: Vec<Brain> = (0..20).map(|_| Brain::random(&mut rng)).collect();
+        assert_eq!(ticks(1, brains.clone()), ticks(1, brains));
+    }
+
+    #[test]
+    fn evolution_improves_the_best_bird() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut population: Vec<Brain> = (0..POPULATION).map(|_| Brain::random(&mut rng)).collect();
+        let first = ticks(0, population.clone())[0];
+        for generation in 1..=20 {
+            let ranked = evaluate(&Tuning::default(), generation, population);
+            let ranked: Vec<Brain> = ranked.into_iter().map(|(brain, _)| brain).collect();
+            population = next_generation(&ranked, &mut rng);
+        }
+        let last = ticks(0, population)[0];
+        assert!(
+            last > first,
+            "best bird survived {first} ticks before and {last} after"
+        );
+    }
+}
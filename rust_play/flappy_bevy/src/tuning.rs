@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::weather::Weather;
+
+pub const TUNING_PATH: &str = "game.tuning.ron";
+
+/// Gameplay numbers, loaded from [`TUNING_PATH`] and reloaded whenever the file changes
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Debug, Clone)]
+pub struct Tuning {
+    pub gravity: f32,
+    pub flap_velocity: f32,
+    pub scroll_speed: f32,
+    /// The background is further away, so it scrolls slower than the pipes and the ground
+    pub background_scroll_speed: f32,
+    pub pipe_spacing: f32,
+    /// Vertical opening between the lower and the upper pipe
+    pub gap: f32,
+    /// How far below the middle of the screen the center of the lower pipe may be
+    pub lower_pipe_depth: Range<f32>,
+    pub difficulty: Difficulty,
+    /// Optional, so tuning files from before there was weather still load. Only for looks, so
+    /// it's left out of the [`Tuning::fingerprint`].
+    #[serde(default, skip_serializing)]
+    pub weather: Weather,
+}
+
+/// How the game gets harder with every point scored
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Difficulty {
+    pub gap_shrink_per_point: f32,
+    pub min_gap: f32,
+    pub speed_up_per_point: f32,
+    pub max_scroll_speed: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            gravity: -1200.0,
+            flap_velocity: 350.0,
+            scroll_speed: 150.0,
+            background_scroll_speed: 30.0,
+            pipe_spacing: 200.0,
+            gap: 130.0,
+            lower_pipe_depth: 70.0..280.0,
+            difficulty: Difficulty {
+                gap_shrink_per_point: 1.0,
+                min_gap: 90.0,
+                speed_up_per_point: 2.0,
+                max_scroll_speed: 250.0,
+            },
+            weather: Weather::default(),
+        }
+    }
+}
+
+impl Tuning {
+    pub fn gap_at(&self, score: u32) -> f32 {
+        (self.gap - self.difficulty.gap_shrink_per_point * score as f32)
+            .max(self.difficulty.min_gap.min(self.gap))
+    }
+
+    pub fn scroll_speed_at(&self, score: u32) -> f32 {
+        (self.scroll_speed + self.difficulty.speed_up_per_point * score as f32)
+            .min(self.difficulty.max_scroll_speed.max(self.scroll_speed))
+    }
+
+    /// Identifies the gameplay numbers, so a replay only plays back with the tuning it was
+    /// recorded with
+    pub fn fingerprint(&self) -> u64 {
+        let text = ron::to_string(self).unwrap_or_default();
+        // FNV-1a, which unlike `DefaultHasher` stays the same across builds
+        text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// Keeps the parallax ratio as the foreground speeds up
+    pub fn background_scroll_speed_at(&self, score: u32) -> f32 {
+        self.background_scroll_speed * self.scroll_speed_at(score) / self.scroll_speed
+    }
+}
+
+#[derive(Default)]
+struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    type Asset = Tuning;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct TuningHandle(Handle<Tuning>);
+
+fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load(TUNING_PATH)));
+}
+
+// Copy the asset into the resource the game reads, on the first load and on every hot reload
+fn apply_tuning(
+    mut events: MessageReader<AssetEvent<Tuning>>,
+    assets: Res<Assets<Tuning>>,
+    handle: Res<TuningHandle>,
+    mut tuning: ResMut<Tuning>,
+) {
+    for event in events.read() {
+        if (event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = assets.get(&handle.0)
+        {
+            info!("Applied tuning from {TUNING_PATH}");
+            *tuning = loaded.clone();
+        }
+    }
+}
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        // The defaults keep the game playable until the file is loaded, or if it's missing
+        app.init_resource::<Tuning>()
+            .init_asset::<Tuning>()
+            .init_asset_loader::<TuningLoader>()
+            .add_systems(PreStartup, load_tuning)
+            .add_systems(Update, apply_tuning);
+    }
+}
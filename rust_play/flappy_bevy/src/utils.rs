@@ -1,8 +1,10 @@
-use rand::{self, Rng};
+use rand::Rng;
 
-pub fn random_pipe_position() -> (f32, f32) {
-    let mut rng = rand::thread_rng();
-    let lower = -rng.gen_range(70.0..280.0); // lower pipe position
+use crate::{constants::PIPE_SIZE, tuning::Tuning};
 
-    (lower, lower + 450.0)
+/// Centers of the lower and the upper pipe, leaving an opening of `gap` between them
+pub fn random_pipe_position(rng: &mut impl Rng, tuning: &Tuning, gap: f32) -> (f32, f32) {
+    let lower = -rng.gen_range(tuning.lower_pipe_depth.clone()); // lower pipe position
+
+    (lower, lower + PIPE_SIZE.y + gap)
 }
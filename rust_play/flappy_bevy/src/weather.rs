@@ -0,0 +1,185 @@
+use std::{f32::consts::TAU, ops::Range};
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    components::*,
+    constants::{GROUND_HEIGHT, GROUND_Y, WINDOW_HEIGHT, WINDOW_WIDTH},
+    rng::GameRng,
+    tuning::Tuning,
+};
+
+const CLOUD_SIZE: Vec2 = Vec2::new(WINDOW_WIDTH / 8.0, WINDOW_HEIGHT / 8.0);
+const RAINDROP_SIZE: Vec2 = Vec2::new(1.5, 12.0);
+
+/// Clouds, the day and night cycle and rain, all just for looks. Anything left out of the
+/// tuning file keeps its default.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Weather {
+    pub clouds: usize,
+    pub cloud_speed: Range<f32>,
+    /// Heights the clouds drift at, from the middle of the screen
+    pub cloud_height: Range<f32>,
+    /// Seconds from noon to noon, no cycle if 0
+    pub day_length: f32,
+    /// Background color at midnight, as sRGB
+    pub night_tint: [f32; 3],
+    pub rain: bool,
+    pub raindrops: usize,
+    pub rain_speed: f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            clouds: 4,
+            cloud_speed: 10.0..25.0,
+            cloud_height: 80.0..220.0,
+            day_length: 120.0,
+            night_tint: [0.35, 0.4, 0.65],
+            rain: false,
+            raindrops: 150,
+            rain_speed: 500.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Raindrop;
+
+fn random_in(rng: &mut impl Rng, range: &Range<f32>) -> f32 {
+    if range.is_empty() {
+        range.start
+    } else {
+        rng.gen_range(range.clone())
+    }
+}
+
+fn random_x(rng: &mut impl Rng) -> f32 {
+    random_in(rng, &(-WINDOW_WIDTH / 2.0..WINDOW_WIDTH / 2.0))
+}
+
+// Spawns or despawns clouds and raindrops whenever the tuning asks for a different amount
+fn update_weather_entities(
+    mut commands: Commands,
+    tuning: Res<Tuning>,
+    asset_server: Res<AssetServer>,
+    mut game_rng: ResMut<GameRng>,
+    clouds: Query<Entity, With<CloudBlue>>,
+    raindrops: Query<Entity, With<Raindrop>>,
+) {
+    if !tuning.is_changed() {
+        return;
+    }
+    let weather = &tuning.weather;
+    let rng = &mut game_rng.decoration;
+
+    for entity in clouds.iter().skip(weather.clouds) {
+        commands.entity(entity).despawn();
+    }
+    for _ in clouds.iter().count()..weather.clouds {
+        // Upside down, so the blue of the sky is at the top
+        let mut transform =
+            Transform::from_xyz(random_x(rng), random_in(rng, &weather.cloud_height), 0.2);
+        transform.rotate(Quat::from_rotation_z(std::f32::consts::PI));
+        commands.spawn((
+            Sprite {
+                image: asset_server.load("texture/cloud.jpg"),
+                custom_size: Some(CLOUD_SIZE),
+                image_mode: SpriteImageMode::Scale(ScalingMode::FillCenter),
+                ..default()
+            },
+            transform,
+            CloudBlue {
+                speed: random_in(rng, &weather.cloud_speed),
+            },
+        ));
+    }
+
+    let raindrops_wanted = if weather.rain { weather.raindrops } else { 0 };
+    for entity in raindrops.iter().skip(raindrops_wanted) {
+        commands.entity(entity).despawn();
+    }
+    for _ in raindrops.iter().count()..raindrops_wanted {
+        commands.spawn((
+            Sprite::from_color(Color::srgba(0.7, 0.8, 1.0, 0.6), RAINDROP_SIZE),
+            Transform::from_xyz(random_x(rng), random_in(rng, &(0.0..WINDOW_HEIGHT)), 2.5),
+            Raindrop,
+        ));
+    }
+}
+
+// Clouds drift left, coming back on the right at a new height once they're gone
+fn drift_clouds(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut game_rng: ResMut<GameRng>,
+    mut query: Query<(&mut CloudBlue, &mut Transform)>,
+) {
+    let rng = &mut game_rng.decoration;
+    for (mut cloud, mut transform) in query.iter_mut() {
+        transform.translation.x -= cloud.speed * time.delta_secs();
+        if transform.translation.x < -(WINDOW_WIDTH + CLOUD_SIZE.x) / 2. {
+            transform.translation.x = (WINDOW_WIDTH + CLOUD_SIZE.x) / 2.;
+            transform.translation.y = random_in(rng, &tuning.weather.cloud_height);
+            cloud.speed = random_in(rng, &tuning.weather.cloud_speed);
+        }
+    }
+}
+
+// Full daylight at the start, darkest halfway through the day
+fn cycle_day_and_night(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut query: Query<&mut Sprite, With<Background>>,
+) {
+    let weather = &tuning.weather;
+    let darkness = if weather.day_length > 0. {
+        (1. - (time.elapsed_secs() / weather.day_length * TAU).cos()) / 2.
+    } else {
+        0.
+    };
+    let [red, green, blue] = weather.night_tint;
+    let tint = Color::WHITE.mix(&Color::srgb(red, green, blue), darkness);
+    for mut sprite in query.iter_mut() {
+        sprite.color = tint;
+    }
+}
+
+// Raindrops fall until they reach the ground, then start again above the screen
+fn fall_rain(
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut game_rng: ResMut<GameRng>,
+    mut query: Query<&mut Transform, With<Raindrop>>,
+) {
+    let rng = &mut game_rng.decoration;
+    let ground_top = GROUND_Y + GROUND_HEIGHT / 2.;
+    for mut transform in query.iter_mut() {
+        transform.translation.y -= tuning.weather.rain_speed * time.delta_secs();
+        if transform.translation.y < ground_top {
+            transform.translation.x = random_x(rng);
+            transform.translation.y =
+                WINDOW_HEIGHT / 2. + random_in(rng, &(0.0..WINDOW_HEIGHT / 2.));
+        }
+    }
+}
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_weather_entities,
+                drift_clouds,
+                cycle_day_and_night,
+                fall_rain,
+            ),
+        );
+    }
+}